@@ -0,0 +1,202 @@
+use crate::converter_impl::ComponentConverter;
+use crate::nlbn::{ComponentSource, EasyedaApi};
+use crate::types::*;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+/// Maximum number of components converted concurrently in a batch
+pub const MAX_CONCURRENT: usize = 4;
+
+/// Convert a single component, turning errors into a failed result
+pub async fn convert_one(lcsc_id: &str, options: &ConversionOptions) -> ConversionResult {
+    convert_one_with(EasyedaApi::new(), lcsc_id, options).await
+}
+
+/// Convert a single component using the given data source
+pub async fn convert_one_with<S: ComponentSource>(
+    source: S,
+    lcsc_id: &str,
+    options: &ConversionOptions,
+) -> ConversionResult {
+    let output_path = PathBuf::from(&options.output_dir);
+    let converter = ComponentConverter::with_source(source, &output_path, options.kicad_format())
+        .with_footprint_origin(options.footprint_origin)
+        .with_pin_grid(options.pin_grid_mil)
+        .with_library_name(&options.library_name)
+        .with_model_path(options.model_path())
+        .with_lib_tables(options.lib_table_target())
+        .with_backups(options.keep_backups)
+        .with_lock_timeout(Duration::from_secs(options.lock_timeout_secs));
+
+    match converter.convert(
+        lcsc_id,
+        options.convert_symbol,
+        options.convert_footprint,
+        options.convert_3d,
+        options.overwrite,
+    ).await {
+        Ok(conv_result) => {
+            log::info!("Conversion successful: {}", conv_result.message);
+
+            ConversionResult {
+                lcsc_id: conv_result.lcsc_id,
+                success: conv_result.success,
+                message: conv_result.message,
+                component_name: conv_result.component_name,
+                files_created: conv_result.files_created,
+            }
+        }
+        Err(e) => {
+            let error_msg = format!("Conversion failed: {}", e);
+            log::error!("{}", error_msg);
+
+            ConversionResult {
+                lcsc_id: lcsc_id.to_string(),
+                success: false,
+                message: error_msg,
+                component_name: None,
+                files_created: Vec::new(),
+            }
+        }
+    }
+}
+
+/// Batch conversion (parallel with semaphore-limited concurrency)
+///
+/// `on_progress` is called when each component starts ("converting") and
+/// finishes ("completed" / "failed").
+pub async fn convert_batch<F>(
+    lcsc_ids: Vec<String>,
+    options: &ConversionOptions,
+    on_progress: F,
+) -> BatchResult
+where
+    F: Fn(&ProgressUpdate) + Send + Sync + 'static,
+{
+    convert_batch_with(EasyedaApi::new(), lcsc_ids, options, on_progress).await
+}
+
+/// Batch conversion using the given data source (cloned into each task)
+pub async fn convert_batch_with<S, F>(
+    source: S,
+    lcsc_ids: Vec<String>,
+    options: &ConversionOptions,
+    on_progress: F,
+) -> BatchResult
+where
+    S: ComponentSource + Clone + Send + Sync + 'static,
+    F: Fn(&ProgressUpdate) + Send + Sync + 'static,
+{
+    log::info!("Batch converting {} components (max {} concurrent)", lcsc_ids.len(), MAX_CONCURRENT);
+
+    let total = lcsc_ids.len();
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT));
+    let completed = Arc::new(AtomicUsize::new(0));
+    let on_progress = Arc::new(on_progress);
+    let mut join_set = JoinSet::new();
+
+    for lcsc_id in lcsc_ids {
+        let sem = semaphore.clone();
+        let source = source.clone();
+        let opts = options.clone();
+        let completed = completed.clone();
+        let on_progress = on_progress.clone();
+
+        join_set.spawn(async move {
+            let _permit = sem.acquire().await.expect("semaphore closed");
+
+            let current = completed.fetch_add(1, Ordering::Relaxed) + 1;
+
+            // Report progress: starting conversion
+            on_progress(&ProgressUpdate {
+                current,
+                total,
+                lcsc_id: lcsc_id.clone(),
+                status: "converting".to_string(),
+            });
+
+            let result = convert_one_with(source, &lcsc_id, &opts).await;
+
+            // Report progress: completed or failed
+            let status = if result.success { "completed" } else { "failed" };
+            on_progress(&ProgressUpdate {
+                current,
+                total,
+                lcsc_id: result.lcsc_id.clone(),
+                status: status.to_string(),
+            });
+
+            result
+        });
+    }
+
+    // Collect all results
+    let mut results = Vec::with_capacity(total);
+    while let Some(join_result) = join_set.join_next().await {
+        match join_result {
+            Ok(result) => results.push(result),
+            Err(e) => {
+                log::error!("Task panicked: {}", e);
+                results.push(ConversionResult {
+                    lcsc_id: "unknown".to_string(),
+                    success: false,
+                    message: format!("Task panicked: {}", e),
+                    component_name: None,
+                    files_created: Vec::new(),
+                });
+            }
+        }
+    }
+
+    let succeeded = results.iter().filter(|r| r.success).count();
+    let failed = total - succeeded;
+
+    BatchResult {
+        total,
+        succeeded,
+        failed,
+        results,
+    }
+}
+
+/// Extract LCSC IDs from free-form text
+/// Supports comma-separated, space-separated, or newline-separated IDs
+pub fn extract_lcsc_ids(input: &str) -> Vec<String> {
+    let mut ids: Vec<String> = Vec::new();
+
+    for token in input.split(|c: char| c == ',' || c == ';' || c.is_whitespace()) {
+        let is_lcsc_id = token.len() > 1
+            && (token.starts_with('C') || token.starts_with('c'))
+            && token[1..].chars().all(|c| c.is_ascii_digit());
+
+        if is_lcsc_id {
+            let id = token.to_uppercase();
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+    }
+
+    ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_lcsc_ids() {
+        let ids = extract_lcsc_ids("C529356, C47773, C2040\nC2875699 C129949 c36658\n");
+        assert_eq!(ids, vec!["C529356", "C47773", "C2040", "C2875699", "C129949", "C36658"]);
+    }
+
+    #[test]
+    fn test_extract_lcsc_ids_skips_invalid_and_duplicates() {
+        let ids = extract_lcsc_ids("C123 foo C12x C123 ,, 456 C");
+        assert_eq!(ids, vec!["C123"]);
+    }
+}
@@ -0,0 +1,231 @@
+//! Headless command-line front end for the NLBN converter.
+//!
+//! Shares the conversion core with the GUI, so it can run in CI or scripted
+//! library builds without a display.
+
+use clap::Parser;
+use nlbn_new_lib::batch;
+use nlbn_new_lib::nlbn::{CachedSource, EasyedaApi, FixtureSource, ResponseCache};
+use nlbn_new_lib::types::{ConversionOptions, FootprintOrigin, ModelPathMode, ProgressUpdate};
+use std::io::Read;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+/// Convert EasyEDA/LCSC components to KiCad libraries
+#[derive(Parser, Debug)]
+#[command(name = "nlbn-cli", version, about)]
+struct Cli {
+    /// LCSC IDs to convert (e.g. C529356). Use "-" to read IDs from stdin
+    ids: Vec<String>,
+
+    /// Read LCSC IDs from a text file (comma, space or newline separated)
+    #[arg(short, long, value_name = "FILE")]
+    input: Vec<PathBuf>,
+
+    /// Output directory for the generated library
+    #[arg(short, long, value_name = "DIR", default_value = "./output")]
+    output: PathBuf,
+
+    /// Library nickname (names the .kicad_sym, .pretty and .3dshapes outputs)
+    #[arg(short = 'n', long, value_name = "NAME", default_value = "nlbn")]
+    library_name: String,
+
+    /// Add the libraries to the sym-lib-table and fp-lib-table of this KiCad project
+    #[arg(long, value_name = "PROJECT_DIR")]
+    register_project: Option<PathBuf>,
+
+    /// Add the libraries to KiCad's global library tables, in the detected
+    /// config directory or the one given
+    #[arg(long, value_name = "CONFIG_DIR", num_args = 0..=1, default_missing_value = "",
+          conflicts_with = "register_project")]
+    register_global: Option<PathBuf>,
+
+    /// Skip symbol conversion
+    #[arg(long)]
+    no_symbol: bool,
+
+    /// Skip footprint conversion
+    #[arg(long)]
+    no_footprint: bool,
+
+    /// Skip 3D model conversion
+    #[arg(long = "no-3d")]
+    no_3d: bool,
+
+    /// Write KiCad 5 legacy libraries
+    #[arg(long)]
+    kicad_v5: bool,
+
+    /// KiCad major version to write files for
+    #[arg(long, value_name = "VERSION", default_value_t = 6,
+          value_parser = clap::value_parser!(u32).range(6..=9), conflicts_with = "kicad_v5")]
+    kicad_version: u32,
+
+    /// How footprints reference 3D models [default: variable with
+    /// --model-path-var, project-relative (${KIPRJMOD}) otherwise]
+    #[arg(long, value_enum, value_name = "MODE")]
+    model_paths: Option<ModelPathMode>,
+
+    /// Reference 3D models through this path variable (e.g. NLBN_3DMODELS),
+    /// set to the output directory on each machine
+    #[arg(long, value_name = "VAR")]
+    model_path_var: Option<String>,
+
+    /// Overwrite components that already exist in the library
+    #[arg(long)]
+    overwrite: bool,
+
+    /// Keep overwritten footprints and 3D models as <file>.<timestamp>.bak
+    #[arg(long, requires = "overwrite")]
+    backup: bool,
+
+    /// Seconds to wait for another process writing the same symbol library
+    #[arg(long, value_name = "SECS", default_value_t = 30)]
+    lock_timeout: u64,
+
+    /// Where to place the footprint origin
+    #[arg(long, value_enum, default_value_t = FootprintOrigin::EasyedaOrigin)]
+    footprint_origin: FootprintOrigin,
+
+    /// Snap symbol pins to this grid, in mils (0 disables snapping)
+    #[arg(long, value_name = "MIL", default_value_t = 50)]
+    pin_grid: u32,
+
+    /// Read recorded component data and 3D models from a fixture directory
+    /// instead of the EasyEDA API (`<LCSC ID>.json`, `<uuid>.obj`, `<uuid>.step`)
+    #[arg(long, value_name = "DIR")]
+    fixtures: Option<PathBuf>,
+
+    /// Cache API responses and 3D models in this directory
+    #[arg(long, value_name = "DIR", conflicts_with = "fixtures")]
+    cache_dir: Option<PathBuf>,
+
+    /// Ignore cached entries and re-download everything (requires --cache-dir)
+    #[arg(long, requires = "cache_dir")]
+    force_refresh: bool,
+
+    /// Print the batch result as JSON instead of a summary
+    #[arg(long)]
+    json: bool,
+
+    /// Increase log verbosity (-v: info, -vv: debug)
+    #[arg(short, long, action = clap::ArgAction::Count)]
+    verbose: u8,
+}
+
+impl Cli {
+    fn conversion_options(&self) -> ConversionOptions {
+        ConversionOptions {
+            output_dir: self.output.to_string_lossy().to_string(),
+            convert_symbol: !self.no_symbol,
+            convert_footprint: !self.no_footprint,
+            convert_3d: !self.no_3d,
+            kicad_v5: self.kicad_v5,
+            kicad_version: self.kicad_version,
+            model_path_mode: self.model_paths,
+            model_path_var: self.model_path_var.clone().unwrap_or_default(),
+            project_relative: None,
+            overwrite: self.overwrite,
+            keep_backups: self.backup,
+            lock_timeout_secs: self.lock_timeout,
+            force_refresh: self.force_refresh,
+            footprint_origin: self.footprint_origin,
+            pin_grid_mil: self.pin_grid,
+            library_name: self.library_name.clone(),
+            lib_table_dir: self.register_project.as_ref()
+                .or(self.register_global.as_ref())
+                .map(|dir| dir.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            lib_table_global: self.register_global.is_some(),
+        }
+    }
+
+    /// Gather IDs from arguments, input files and stdin (in that order)
+    fn collect_ids(&self) -> std::io::Result<Vec<String>> {
+        let mut text = String::new();
+
+        for id in &self.ids {
+            if id == "-" {
+                std::io::stdin().read_to_string(&mut text)?;
+            } else {
+                text.push_str(id);
+            }
+            text.push('\n');
+        }
+
+        for path in &self.input {
+            text.push_str(&std::fs::read_to_string(path)?);
+            text.push('\n');
+        }
+
+        Ok(batch::extract_lcsc_ids(&text))
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let default_level = match cli.verbose {
+        0 => "warn",
+        1 => "info",
+        _ => "debug",
+    };
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(default_level))
+        .init();
+
+    let lcsc_ids = match cli.collect_ids() {
+        Ok(ids) => ids,
+        Err(e) => {
+            eprintln!("error: failed to read LCSC IDs: {}", e);
+            return ExitCode::from(2);
+        }
+    };
+
+    if lcsc_ids.is_empty() {
+        eprintln!("error: no valid LCSC IDs given (expected e.g. C529356)");
+        return ExitCode::from(2);
+    }
+
+    let options = cli.conversion_options();
+    let quiet = cli.json;
+
+    let report_progress = move |progress: &ProgressUpdate| {
+        if !quiet && progress.status != "converting" {
+            eprintln!("[{}/{}] {} {}", progress.current, progress.total, progress.lcsc_id, progress.status);
+        }
+    };
+
+    let batch_result = match (&cli.fixtures, &cli.cache_dir) {
+        (Some(dir), _) => {
+            let source = FixtureSource::new(dir);
+            batch::convert_batch_with(source, lcsc_ids, &options, report_progress).await
+        }
+        (None, Some(dir)) => {
+            let source = CachedSource::new(EasyedaApi::new(), ResponseCache::new(dir), cli.force_refresh);
+            batch::convert_batch_with(source, lcsc_ids, &options, report_progress).await
+        }
+        (None, None) => batch::convert_batch(lcsc_ids, &options, report_progress).await,
+    };
+
+    if cli.json {
+        match serde_json::to_string_pretty(&batch_result) {
+            Ok(json) => println!("{}", json),
+            Err(e) => eprintln!("error: failed to serialize result: {}", e),
+        }
+    } else {
+        for result in batch_result.results.iter().filter(|r| !r.success) {
+            println!("FAILED {}: {}", result.lcsc_id, result.message);
+        }
+        println!(
+            "Converted {}/{} components ({} failed) into {}",
+            batch_result.succeeded, batch_result.total, batch_result.failed, options.output_dir
+        );
+    }
+
+    if batch_result.failed > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
@@ -1,12 +1,9 @@
 use tauri::{AppHandle, Manager, Emitter};
 use crate::types::*;
 use crate::history::HistoryManager;
-use crate::converter_impl::ComponentConverter;
+use crate::batch;
+use crate::nlbn::{CachedSource, EasyedaApi, ResponseCache};
 use std::path::PathBuf;
-use std::sync::Arc;
-use std::sync::atomic::{AtomicUsize, Ordering};
-use tokio::sync::Semaphore;
-use tokio::task::JoinSet;
 
 /// Single component conversion
 #[tauri::command]
@@ -17,65 +14,15 @@ pub async fn convert_component(
 ) -> std::result::Result<ConversionResult, String> {
     log::info!("Converting component: {}", lcsc_id);
 
-    // Create converter
-    let output_path = PathBuf::from(&options.output_dir);
-    let converter = ComponentConverter::new(&output_path, options.kicad_v5);
+    let source = component_source(&app, &options);
+    let result = batch::convert_one_with(source, &lcsc_id, &options).await;
 
-    // Perform conversion
-    let result = converter.convert(
-        &lcsc_id,
-        options.convert_symbol,
-        options.convert_footprint,
-        options.convert_3d,
-        options.overwrite,
-    ).await;
-
-    match result {
-        Ok(conv_result) => {
-            log::info!("Conversion successful: {}", conv_result.message);
-
-            // Convert to API result type
-            let api_result = ConversionResult {
-                lcsc_id: conv_result.lcsc_id.clone(),
-                success: conv_result.success,
-                message: conv_result.message.clone(),
-                component_name: conv_result.component_name.clone(),
-                files_created: conv_result.files_created.clone(),
-            };
-
-            // Save to history
-            if conv_result.success {
-                if let Ok(app_dir) = app.path().app_data_dir() {
-                    let _ = std::fs::create_dir_all(&app_dir);
-
-                    if let Ok(history) = HistoryManager::new(app_dir) {
-                        let _ = history.add_entry(&HistoryEntry {
-                            id: 0,
-                            lcsc_id: conv_result.lcsc_id,
-                            component_name: conv_result.component_name,
-                            success: conv_result.success,
-                            timestamp: chrono::Local::now().to_rfc3339(),
-                            output_dir: options.output_dir,
-                        });
-                    }
-                }
-            }
-
-            Ok(api_result)
-        }
-        Err(e) => {
-            let error_msg = format!("Conversion failed: {}", e);
-            log::error!("{}", error_msg);
-
-            Ok(ConversionResult {
-                lcsc_id,
-                success: false,
-                message: error_msg,
-                component_name: None,
-                files_created: Vec::new(),
-            })
-        }
+    // Save to history
+    if result.success {
+        record_history(&app, &result, &options.output_dir);
     }
+
+    Ok(result)
 }
 
 /// Batch conversion (parallel with semaphore-limited concurrency)
@@ -85,125 +32,49 @@ pub async fn batch_convert(
     options: ConversionOptions,
     app: AppHandle,
 ) -> std::result::Result<BatchResult, String> {
-    log::info!("Batch converting {} components (max 4 concurrent)", lcsc_ids.len());
-
-    let total = lcsc_ids.len();
-    let semaphore = Arc::new(Semaphore::new(4));
-    let completed = Arc::new(AtomicUsize::new(0));
-    let mut join_set = JoinSet::new();
-
-    for lcsc_id in lcsc_ids {
-        let sem = semaphore.clone();
-        let opts = options.clone();
-        let handle = app.clone();
-        let completed = completed.clone();
-        let total = total;
-
-        join_set.spawn(async move {
-            let _permit = sem.acquire().await.expect("semaphore closed");
-
-            let current = completed.fetch_add(1, Ordering::Relaxed) + 1;
-
-            // Emit progress: starting conversion
-            let _ = handle.emit("conversion-progress", &ProgressUpdate {
-                current,
-                total,
-                lcsc_id: lcsc_id.clone(),
-                status: "converting".to_string(),
-            });
+    let source = component_source(&app, &options);
+    let handle = app.clone();
+    let batch_result = batch::convert_batch_with(source, lcsc_ids, &options, move |progress| {
+        let _ = handle.emit("conversion-progress", progress);
+    }).await;
+
+    // Save successful conversions to history
+    for result in batch_result.results.iter().filter(|r| r.success) {
+        record_history(&app, result, &options.output_dir);
+    }
 
-            // Perform conversion
-            let output_path = PathBuf::from(&opts.output_dir);
-            let converter = ComponentConverter::new(&output_path, opts.kicad_v5);
+    Ok(batch_result)
+}
 
-            let result = match converter.convert(
-                &lcsc_id,
-                opts.convert_symbol,
-                opts.convert_footprint,
-                opts.convert_3d,
-                opts.overwrite,
-            ).await {
-                Ok(conv_result) => {
-                    log::info!("Conversion successful: {}", conv_result.message);
+/// Directory of the persistent response cache
+fn cache_dir(app: &AppHandle) -> PathBuf {
+    app.path().app_data_dir()
+        .unwrap_or_else(|_| std::env::temp_dir().join("nlbn"))
+        .join("cache")
+}
 
-                    // Save to history
-                    if conv_result.success {
-                        if let Ok(app_dir) = handle.path().app_data_dir() {
-                            let _ = std::fs::create_dir_all(&app_dir);
-                            if let Ok(history) = HistoryManager::new(app_dir) {
-                                let _ = history.add_entry(&HistoryEntry {
-                                    id: 0,
-                                    lcsc_id: conv_result.lcsc_id.clone(),
-                                    component_name: conv_result.component_name.clone(),
-                                    success: conv_result.success,
-                                    timestamp: chrono::Local::now().to_rfc3339(),
-                                    output_dir: opts.output_dir.clone(),
-                                });
-                            }
-                        }
-                    }
+/// EasyEDA API client backed by the persistent response cache
+fn component_source(app: &AppHandle, options: &ConversionOptions) -> CachedSource<EasyedaApi> {
+    let cache = ResponseCache::new(&cache_dir(app));
+    CachedSource::new(EasyedaApi::new(), cache, options.force_refresh)
+}
 
-                    ConversionResult {
-                        lcsc_id: conv_result.lcsc_id,
-                        success: conv_result.success,
-                        message: conv_result.message,
-                        component_name: conv_result.component_name,
-                        files_created: conv_result.files_created,
-                    }
-                }
-                Err(e) => {
-                    let error_msg = format!("Conversion failed: {}", e);
-                    log::error!("{}", error_msg);
-                    ConversionResult {
-                        lcsc_id: lcsc_id.clone(),
-                        success: false,
-                        message: error_msg,
-                        component_name: None,
-                        files_created: Vec::new(),
-                    }
-                }
-            };
+/// Record a successful conversion in the history database
+fn record_history(app: &AppHandle, result: &ConversionResult, output_dir: &str) {
+    if let Ok(app_dir) = app.path().app_data_dir() {
+        let _ = std::fs::create_dir_all(&app_dir);
 
-            // Emit progress: completed or failed
-            let status = if result.success { "completed" } else { "failed" };
-            let _ = handle.emit("conversion-progress", &ProgressUpdate {
-                current,
-                total,
+        if let Ok(history) = HistoryManager::new(app_dir) {
+            let _ = history.add_entry(&HistoryEntry {
+                id: 0,
                 lcsc_id: result.lcsc_id.clone(),
-                status: status.to_string(),
+                component_name: result.component_name.clone(),
+                success: result.success,
+                timestamp: chrono::Local::now().to_rfc3339(),
+                output_dir: output_dir.to_string(),
             });
-
-            result
-        });
-    }
-
-    // Collect all results
-    let mut results = Vec::with_capacity(total);
-    while let Some(join_result) = join_set.join_next().await {
-        match join_result {
-            Ok(result) => results.push(result),
-            Err(e) => {
-                log::error!("Task panicked: {}", e);
-                results.push(ConversionResult {
-                    lcsc_id: "unknown".to_string(),
-                    success: false,
-                    message: format!("Task panicked: {}", e),
-                    component_name: None,
-                    files_created: Vec::new(),
-                });
-            }
         }
     }
-
-    let succeeded = results.iter().filter(|r| r.success).count();
-    let failed = total - succeeded;
-
-    Ok(BatchResult {
-        total,
-        succeeded,
-        failed,
-        results,
-    })
 }
 
 /// Select output directory
@@ -221,6 +92,22 @@ pub async fn select_output_directory(app: AppHandle) -> std::result::Result<Stri
     }
 }
 
+/// Select the KiCad project directory to register the libraries in
+#[tauri::command]
+pub async fn select_project_directory(app: AppHandle) -> std::result::Result<String, String> {
+    use tauri_plugin_dialog::DialogExt;
+
+    let path = app.dialog()
+        .file()
+        .set_title("Select KiCad project directory")
+        .blocking_pick_folder();
+
+    match path {
+        Some(p) => Ok(p.as_path().unwrap().display().to_string()),
+        None => Err("No directory selected".to_string()),
+    }
+}
+
 /// Get history
 #[tauri::command]
 pub async fn get_history(app: AppHandle, limit: usize) -> std::result::Result<Vec<HistoryEntry>, String> {
@@ -249,6 +136,28 @@ pub async fn clear_history(app: AppHandle) -> std::result::Result<(), String> {
         .map_err(|e| format!("Failed to clear history: {}", e))
 }
 
+/// Get response cache location and size
+#[tauri::command]
+pub async fn get_cache_info(app: AppHandle) -> std::result::Result<CacheInfo, String> {
+    let cache = ResponseCache::new(&cache_dir(&app));
+    let stats = cache.stats();
+
+    Ok(CacheInfo {
+        path: cache.dir().display().to_string(),
+        components: stats.components,
+        models: stats.models,
+        total_bytes: stats.total_bytes,
+    })
+}
+
+/// Clear response cache
+#[tauri::command]
+pub async fn clear_cache(app: AppHandle) -> std::result::Result<(), String> {
+    ResponseCache::new(&cache_dir(&app))
+        .clear()
+        .map_err(|e| format!("Failed to clear cache: {}", e))
+}
+
 /// Import LCSC IDs from text file
 #[tauri::command]
 pub async fn import_ids_from_file(app: AppHandle) -> std::result::Result<String, String> {
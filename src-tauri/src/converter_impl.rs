@@ -1,38 +1,109 @@
 use std::path::Path;
-use crate::nlbn::easyeda::{EasyedaApi, models::{ComponentData, EeSymbol, EeFootprint}};
+use crate::nlbn::easyeda::{
+    EasyedaApi, ComponentSource,
+    models::{ComponentData, EeSymbol, EeFootprint, EePath, EeArc},
+    svg_parser::{parse_svg_path, SvgCommand},
+};
 use crate::nlbn::kicad::{
-    symbol::{KiSymbol, KiPin, KiRectangle, KiCircle, KiArc, KiPolyline, PinType, PinStyle},
+    symbol::{KiSymbol, KiPin, KiRectangle, KiCircle, KiArc, KiPolyline, KiText as SymbolKiText, PinType, PinStyle},
     footprint::{
-        KiFootprint, KiPad, KiTrack, KiText, KiLine, Ki3dModel, Drill, PadType, PadShape,
-        KiCircle as FootprintKiCircle, KiArc as FootprintKiArc
+        KiFootprint, KiPad, KiText, KiLine, Ki3dModel, Drill, PadType, PadShape,
+        KiCircle as FootprintKiCircle, KiArc as FootprintKiArc,
+        KiRectangle as FootprintKiRectangle, KiPolygon, KiZone,
     },
+    layers::{map_graphic_layer, map_pad_layers_smd, map_pad_layers_tht},
+    LibTableKind, PinMove,
     SymbolExporter, FootprintExporter, ModelExporter,
 };
 use crate::nlbn::{LibraryManager, KicadVersion, Converter};
-use crate::nlbn::error::Result;
+use crate::nlbn::error::{AppError, Result};
+use crate::types::{FootprintOrigin, LibTableTarget, ModelPath};
+
+/// Number of segments used to approximate a symbol ellipse
+const ELLIPSE_SEGMENTS: usize = 36;
+
+/// Anchor size of custom (polygon) pads, in EasyEDA units
+const CUSTOM_PAD_ANCHOR: f64 = 1.0;
+
+/// EasyEDA units per millimetre (1 px = 10 mil = 0.254 mm)
+const PX_PER_MM: f64 = 1.0 / 0.254;
+
+/// Symbol grid in EasyEDA units (100 mil); symbol origins are snapped to it
+const SYMBOL_GRID: f64 = 10.0;
 
 /// High-level converter that orchestrates the entire conversion process
-pub struct ComponentConverter {
-    api: EasyedaApi,
+pub struct ComponentConverter<S: ComponentSource = EasyedaApi> {
+    api: S,
     library_manager: LibraryManager,
     kicad_version: KicadVersion,
+    footprint_origin: FootprintOrigin,
+    pin_grid_mil: u32,
+    model_path: ModelPath,
+    lib_tables: Option<LibTableTarget>,
 }
 
-impl ComponentConverter {
-    pub fn new(output_path: &Path, kicad_v5: bool) -> Self {
-        let kicad_version = if kicad_v5 {
-            KicadVersion::V5
-        } else {
-            KicadVersion::V6
-        };
+impl ComponentConverter<EasyedaApi> {
+    pub fn new(output_path: &Path, kicad_version: KicadVersion) -> Self {
+        Self::with_source(EasyedaApi::new(), output_path, kicad_version)
+    }
+}
 
+impl<S: ComponentSource> ComponentConverter<S> {
+    /// Create a converter that reads component data from a custom source
+    pub fn with_source(source: S, output_path: &Path, kicad_version: KicadVersion) -> Self {
         Self {
-            api: EasyedaApi::new(),
-            library_manager: LibraryManager::new(output_path),
+            api: source,
+            library_manager: LibraryManager::new(output_path).with_kicad_version(kicad_version),
             kicad_version,
+            footprint_origin: FootprintOrigin::default(),
+            pin_grid_mil: 50,
+            model_path: ModelPath::default(),
+            lib_tables: None,
         }
     }
 
+    /// Choose where the footprint origin is placed
+    pub fn with_footprint_origin(mut self, origin: FootprintOrigin) -> Self {
+        self.footprint_origin = origin;
+        self
+    }
+
+    /// Use `name` as the library nickname instead of `nlbn`
+    pub fn with_library_name(mut self, name: &str) -> Self {
+        self.library_manager = self.library_manager.with_library_name(name);
+        self
+    }
+
+    /// Choose how footprints reference their 3D models
+    pub fn with_model_path(mut self, model_path: ModelPath) -> Self {
+        self.model_path = model_path;
+        self
+    }
+
+    /// Register the converted libraries in the library tables of `target`
+    pub fn with_lib_tables(mut self, target: Option<LibTableTarget>) -> Self {
+        self.lib_tables = target;
+        self
+    }
+
+    /// Wait up to `timeout` for other processes updating the symbol library
+    pub fn with_lock_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.library_manager = self.library_manager.with_lock_timeout(timeout);
+        self
+    }
+
+    /// Keep overwritten footprints and 3D models as backup files
+    pub fn with_backups(mut self, keep_backups: bool) -> Self {
+        self.library_manager = self.library_manager.with_backups(keep_backups);
+        self
+    }
+
+    /// Snap symbol pins to a grid of `mil` mils (0 disables snapping)
+    pub fn with_pin_grid(mut self, mil: u32) -> Self {
+        self.pin_grid_mil = mil;
+        self
+    }
+
     /// Convert a component from LCSC/EasyEDA to KiCad
     pub async fn convert(
         &self,
@@ -53,12 +124,24 @@ impl ComponentConverter {
 
         let mut files_created = Vec::new();
         let mut skipped_items = Vec::new();
+        let mut pin_moves = Vec::new();
         let component_name = sanitize_component_name(&component_data.title);
 
+        // Link the symbol to its footprint if it is converted now or was before
+        let has_footprint = (convert_footprint && !component_data.package_detail.is_empty())
+            || self.library_manager.footprint_exists(&component_name);
+        let footprint_ref = has_footprint.then(|| self.library_manager.footprint_reference(&component_name));
+
         // Convert symbol
         if convert_symbol && !component_data.data_str.is_empty() {
             log::info!("Converting symbol...");
-            let (symbol_file, written) = self.convert_symbol(&component_data, &component_name, overwrite)?;
+            let (symbol_file, written, moves) = self.convert_symbol(
+                &component_data,
+                &component_name,
+                footprint_ref.as_deref(),
+                overwrite,
+            ).await?;
+            pin_moves = moves;
             if written {
                 files_created.push(symbol_file.to_string_lossy().to_string());
             } else {
@@ -66,21 +149,16 @@ impl ComponentConverter {
             }
         }
 
-        // Convert footprint
-        if convert_footprint && !component_data.package_detail.is_empty() {
-            log::info!("Converting footprint...");
-            let footprint_file = self.convert_footprint(&component_data, &component_name)?;
-            files_created.push(footprint_file.to_string_lossy().to_string());
-        }
-
-        // Convert 3D model
+        // Convert 3D model first so the footprint can reference what was downloaded
+        let mut model_files = Vec::new();
         if convert_3d {
             if let Some(model_info) = &component_data.model_3d {
                 log::info!("Converting 3D model...");
-                match self.convert_3d_model(&model_info.uuid, &component_name).await {
-                    Ok(model_files) => {
-                        for file in model_files {
-                            files_created.push(file.to_string_lossy().to_string());
+                match self.convert_3d_model(&model_info.uuid, &component_name, overwrite).await {
+                    Ok((files, skipped)) => {
+                        model_files = files;
+                        if skipped {
+                            skipped_items.push("3D model (already exists)");
                         }
                     }
                     Err(e) => {
@@ -92,12 +170,66 @@ impl ComponentConverter {
             }
         }
 
+        // Convert footprint, or add a newly downloaded model to the existing one
+        let model_file = self.model_file(&component_name);
+        let mut footprint_written = false;
+        if convert_footprint && !component_data.package_detail.is_empty() {
+            log::info!("Converting footprint...");
+            match self.convert_footprint(&component_data, &component_name, model_file.as_deref(), overwrite)? {
+                Some(footprint_file) => {
+                    files_created.push(footprint_file.to_string_lossy().to_string());
+                    footprint_written = true;
+                }
+                None => skipped_items.push("Footprint (already exists)"),
+            }
+        }
+        let mut model_attached = false;
+        if let Some(model_file) = model_file.as_deref().filter(|_| !footprint_written && !model_files.is_empty()) {
+            model_attached = self.attach_model(&component_data, &component_name, model_file)?;
+        }
+        files_created.extend(model_files.iter().map(|file| file.to_string_lossy().to_string()));
+
         // Build result message
         let mut message = format!("Successfully converted {} to {}", lcsc_id, component_name);
+        if !pin_moves.is_empty() {
+            let converter = Converter::new(self.kicad_version);
+            let max_move = pin_moves.iter()
+                .map(|m| converter.px_to_mm(m.dx.hypot(m.dy)))
+                .fold(0.0, f64::max);
+            message.push_str(&format!(
+                "\nMoved {} pin(s) onto the {} mil grid (up to {:.3} mm)",
+                pin_moves.len(), self.pin_grid_mil, max_move
+            ));
+        }
+        if model_attached {
+            message.push_str("\nAdded the 3D model to the existing footprint");
+        }
         if !skipped_items.is_empty() {
             message.push_str(&format!("\nSkipped: {} (enable overwrite to update)", skipped_items.join(", ")));
         }
 
+        // Register the libraries in the KiCad library tables
+        if let Some(target) = &self.lib_tables {
+            let kinds = [
+                convert_symbol.then_some(LibTableKind::Symbol),
+                convert_footprint.then_some(LibTableKind::Footprint),
+            ];
+            for kind in kinds.into_iter().flatten() {
+                match self.library_manager.register_library(&target.dir, kind, target.project) {
+                    Ok(true) => message.push_str(&format!(
+                        "\nRegistered {} in {}",
+                        self.library_manager.library_name(),
+                        target.dir.join(kind.file_name()).display()
+                    )),
+                    Ok(false) => {}
+                    Err(e) => {
+                        log::warn!("Failed to update {}: {}", kind.file_name(), e);
+                        message.push_str(&format!("\nCould not update {}: {}", kind.file_name(), e));
+                    }
+                }
+            }
+        }
+
         Ok(ConversionResult {
             lcsc_id: lcsc_id.to_string(),
             component_name: Some(component_name.clone()),
@@ -107,34 +239,164 @@ impl ComponentConverter {
         })
     }
 
-    fn convert_symbol(
+    /// Parse, convert and export the symbol of a component without writing it
+    ///
+    /// `footprint` is the `<library>:<footprint>` the symbol is linked to, if any.
+    pub fn export_symbol(
         &self,
         component_data: &ComponentData,
         component_name: &str,
-        overwrite: bool,
-    ) -> Result<(std::path::PathBuf, bool)> {
+        footprint: Option<&str>,
+    ) -> Result<String> {
+        self.export_symbol_with_pin_moves(component_data, component_name, footprint)
+            .map(|(symbol_data, _)| symbol_data)
+    }
+
+    /// Like `export_symbol`, also returning the pins moved by grid snapping
+    fn export_symbol_with_pin_moves(
+        &self,
+        component_data: &ComponentData,
+        component_name: &str,
+        footprint: Option<&str>,
+    ) -> Result<(String, Vec<PinMove>)> {
         use crate::nlbn::easyeda::SymbolImporter;
 
-        // Parse EasyEDA symbol data
-        let ee_symbol = SymbolImporter::parse(&component_data.data_str)?;
+        // Parse EasyEDA symbol data (one symbol per unit)
+        let ee_units = SymbolImporter::parse_units(&component_data.data_str, &component_data.sub_parts)?;
 
         // Convert to KiCad symbol
-        let ki_symbol = self.convert_ee_symbol_to_ki(
-            &ee_symbol,
+        let mut ki_symbol = self.convert_ee_symbol_to_ki(
+            &ee_units,
             component_name,
             &component_data.manufacturer,
             &component_data.datasheet,
             &component_data.lcsc_id,
             &component_data.jlc_id,
         )?;
+        if !component_data.prefix.is_empty() {
+            ki_symbol.reference = component_data.prefix.clone();
+        }
+        ki_symbol.footprint = footprint.unwrap_or_default().to_string();
+        ki_symbol.fp_filters = footprint_filters(&component_data.package_name, footprint);
+
+        // Center the symbol on its EasyEDA origin, kept on the grid so the
+        // pins stay on it too
+        let converter = Converter::new(self.kicad_version);
+        let origin_x = (component_data.bbox_x / SYMBOL_GRID).round() * SYMBOL_GRID;
+        let origin_y = (component_data.bbox_y / SYMBOL_GRID).round() * SYMBOL_GRID;
+        ki_symbol.translate(-origin_x, -converter.flip_y(origin_y));
+
+        // Put pins on the schematic grid (1 px = 10 mil)
+        let pin_moves = if self.pin_grid_mil > 0 {
+            ki_symbol.snap_pins_to_grid(self.pin_grid_mil as f64 / 10.0)
+        } else {
+            Vec::new()
+        };
+        for pin_move in &pin_moves {
+            log::info!(
+                "Moved pin {} (unit {}) by ({:.3}, {:.3}) mm to the {} mil grid",
+                pin_move.number,
+                pin_move.unit,
+                converter.px_to_mm(pin_move.dx),
+                converter.px_to_mm(pin_move.dy),
+                self.pin_grid_mil
+            );
+        }
 
         // Export to KiCad format
         let exporter = SymbolExporter::new(self.kicad_version);
-        let symbol_data = exporter.export(&ki_symbol)?;
+        Ok((exporter.export(&ki_symbol)?, pin_moves))
+    }
 
-        // Write to library file
-        let lib_path = self.library_manager.get_symbol_lib_path(self.kicad_version == KicadVersion::V5);
-        let written = self.library_manager.add_or_update_component(&lib_path, component_name, &symbol_data, overwrite)?;
+    /// Parse, convert and export the footprint of a component without writing it
+    ///
+    /// `model_file` is the file in the `.3dshapes` directory the footprint
+    /// references; without one the footprint has no 3D model.
+    pub fn export_footprint(
+        &self,
+        component_data: &ComponentData,
+        component_name: &str,
+        model_file: Option<&str>,
+    ) -> Result<String> {
+        let ki_footprint = self.build_footprint(component_data, component_name, model_file)?;
+
+        // Export to KiCad format
+        let exporter = FootprintExporter::new(self.kicad_version);
+        exporter.export(&ki_footprint)
+    }
+
+    /// Parse and convert the footprint of a component, with the chosen origin at (0, 0)
+    fn build_footprint(
+        &self,
+        component_data: &ComponentData,
+        component_name: &str,
+        model_file: Option<&str>,
+    ) -> Result<KiFootprint> {
+        use crate::nlbn::easyeda::FootprintImporter;
+
+        // Parse EasyEDA footprint data
+        let ee_footprint = FootprintImporter::parse(&component_data.package_detail)?;
+
+        // Convert to KiCad footprint
+        let mut ki_footprint = self.convert_ee_footprint_to_ki(&ee_footprint, component_name, model_file)?;
+
+        // Move the chosen origin to (0, 0)
+        let converter = Converter::new(self.kicad_version);
+        let package_origin = (component_data.package_bbox_x, component_data.package_bbox_y);
+        let bounds = match self.footprint_origin {
+            FootprintOrigin::EasyedaOrigin => None,
+            FootprintOrigin::BboxCenter => ki_footprint.bounds(),
+            FootprintOrigin::PadCenter => ki_footprint.pad_bounds(),
+        };
+        let (origin_x, origin_y) = bounds.map_or(package_origin, |(min_x, min_y, max_x, max_y)| {
+            ((min_x + max_x) / 2.0, (min_y + max_y) / 2.0)
+        });
+        ki_footprint.translate(-origin_x, -origin_y);
+
+        // Place the 3D model where EasyEDA puts it, relative to the new origin
+        let is_smd = ki_footprint.is_smd();
+        if let (Some(model), Some(info)) = (&mut ki_footprint.model_3d, &component_data.model_3d) {
+            let (model_x, model_y) = info.origin.unwrap_or((component_data.package_bbox_x, component_data.package_bbox_y));
+            // EasyEDA's height only applies to SMD models; THT models sit on the board
+            let height = if is_smd { -converter.px_to_mm(info.z) } else { 0.0 };
+
+            // KiCad model offsets point Y up and rotate the other way round
+            // (adding 0.0 turns -0.0 into 0.0, which would print as "-0.0000")
+            model.offset = (
+                converter.px_to_mm(model_x - origin_x) + 0.0,
+                -converter.px_to_mm(model_y - origin_y) + 0.0,
+                height + 0.0,
+            );
+            let (rx, ry, rz) = info.rotation;
+            model.rotate = (
+                (360.0 - rx).rem_euclid(360.0),
+                (360.0 - ry).rem_euclid(360.0),
+                (360.0 - rz).rem_euclid(360.0),
+            );
+        }
+
+        Ok(ki_footprint)
+    }
+
+    async fn convert_symbol(
+        &self,
+        component_data: &ComponentData,
+        component_name: &str,
+        footprint: Option<&str>,
+        overwrite: bool,
+    ) -> Result<(std::path::PathBuf, bool, Vec<PinMove>)> {
+        let (symbol_data, pin_moves) = self.export_symbol_with_pin_moves(component_data, component_name, footprint)?;
+
+        // Write to library file; this may wait for another process's lock,
+        // so keep it off the async worker threads
+        let lib_path = self.library_manager.get_symbol_lib_path(self.kicad_version.is_legacy());
+        let library_manager = self.library_manager.clone();
+        let (path, name) = (lib_path.clone(), component_name.to_string());
+        let written = tokio::task::spawn_blocking(move || {
+            library_manager.add_or_update_component(&path, &name, &symbol_data, overwrite)
+        })
+        .await
+        .map_err(|e| AppError::Other(format!("Symbol library update failed: {}", e)))??;
 
         if written {
             log::info!("Symbol written to: {}", lib_path.display());
@@ -142,67 +404,114 @@ impl ComponentConverter {
             log::info!("Symbol already exists, skipped: {}", lib_path.display());
         }
 
-        Ok((lib_path, written))
+        Ok((lib_path, written, pin_moves))
     }
 
     fn convert_footprint(
         &self,
         component_data: &ComponentData,
         component_name: &str,
-    ) -> Result<std::path::PathBuf> {
-        use crate::nlbn::easyeda::FootprintImporter;
+        model_file: Option<&str>,
+        overwrite: bool,
+    ) -> Result<Option<std::path::PathBuf>> {
+        let footprint_data = self.export_footprint(component_data, component_name, model_file)?;
 
-        // Parse EasyEDA footprint data
-        let ee_footprint = FootprintImporter::parse(&component_data.package_detail)?;
+        // Write footprint file (None if it exists and overwrite is off)
+        self.library_manager.write_footprint(component_name, &footprint_data, overwrite)
+    }
 
-        // Convert to KiCad footprint
-        let ki_footprint = self.convert_ee_footprint_to_ki(&ee_footprint, component_name)?;
+    /// Add the 3D model to a footprint written by an earlier run without one
+    ///
+    /// Returns false if the footprint does not exist or already has a model.
+    fn attach_model(&self, component_data: &ComponentData, component_name: &str, model_file: &str) -> Result<bool> {
+        if !self.library_manager.footprint_exists(component_name) {
+            return Ok(false);
+        }
 
-        // Export to KiCad format
-        let exporter = FootprintExporter::new();
-        let footprint_data = exporter.export(&ki_footprint)?;
+        let ki_footprint = self.build_footprint(component_data, component_name, Some(model_file))?;
+        let Some(model) = &ki_footprint.model_3d else {
+            return Ok(false);
+        };
+
+        let exporter = FootprintExporter::new(self.kicad_version);
+        self.library_manager.add_model_to_footprint(component_name, &exporter.format_3d_model(model))
+    }
+
+    /// Model file the footprint should reference: the STEP model if one was
+    /// downloaded (by this or an earlier run), otherwise the VRML model;
+    /// `None` if there is neither
+    fn model_file(&self, component_name: &str) -> Option<String> {
+        [format!("{}.step", component_name), format!("{}.wrl", component_name)]
+            .into_iter()
+            .find(|file| self.library_manager.model_path(file).exists())
+    }
 
-        // Write footprint file
-        let footprint_path = self.library_manager.write_footprint(component_name, &footprint_data)?;
+    /// KiCad path of a model file, according to the configured path mode
+    fn model_reference(&self, model_file: &str) -> String {
+        let library_name = self.library_manager.library_name();
 
-        Ok(footprint_path)
+        match &self.model_path {
+            ModelPath::ProjectRelative => {
+                format!("${{KIPRJMOD}}/{}.3dshapes/{}", library_name, model_file)
+            }
+            ModelPath::Variable(var) => {
+                format!("${{{}}}/{}.3dshapes/{}", var, library_name, model_file)
+            }
+            ModelPath::Absolute => {
+                let path = self.library_manager.model_path(model_file);
+                let path = std::path::absolute(&path).unwrap_or(path);
+                // KiCad accepts forward slashes on every platform
+                path.to_string_lossy().replace('\\', "/")
+            }
+        }
     }
 
+    /// Download and write the 3D models; returns the files written and
+    /// whether existing models were kept because `overwrite` is off
     async fn convert_3d_model(
         &self,
         uuid: &str,
         component_name: &str,
-    ) -> Result<Vec<std::path::PathBuf>> {
+        overwrite: bool,
+    ) -> Result<(Vec<std::path::PathBuf>, bool)> {
         let mut files = Vec::new();
+        let mut skipped = false;
+        let exists = |ext: &str| self.library_manager.model_path(&format!("{}.{}", component_name, ext)).exists();
 
-        // Download OBJ model
-        let obj_data = self.api.download_3d_obj(uuid).await?;
+        if !overwrite && exists("wrl") {
+            skipped = true;
+        } else {
+            // Download OBJ model
+            let obj_data = self.api.download_3d_obj(uuid).await?;
 
-        // Convert OBJ to VRML
-        let model_exporter = ModelExporter::new();
-        let wrl_data = model_exporter.obj_to_wrl(&obj_data)?;
+            // Convert OBJ to VRML
+            let model_exporter = ModelExporter::new();
+            let wrl_data = model_exporter.obj_to_wrl(&obj_data)?;
 
-        // Write VRML model
-        let wrl_path = self.library_manager.write_wrl_model(component_name, &wrl_data)?;
-        files.push(wrl_path);
+            // Write VRML model
+            files.extend(self.library_manager.write_wrl_model(component_name, &wrl_data, overwrite)?);
+        }
 
-        // Try to download STEP model (may fail)
-        match self.api.download_3d_step(uuid).await {
-            Ok(step_data) => {
-                let step_path = self.library_manager.write_step_model(component_name, &step_data)?;
-                files.push(step_path);
-            }
-            Err(e) => {
-                log::warn!("STEP model not available: {}", e);
+        if !overwrite && exists("step") {
+            skipped = true;
+        } else {
+            // Try to download STEP model (may fail)
+            match self.api.download_3d_step(uuid).await {
+                Ok(step_data) => {
+                    files.extend(self.library_manager.write_step_model(component_name, &step_data, overwrite)?);
+                }
+                Err(e) => {
+                    log::warn!("STEP model not available: {}", e);
+                }
             }
         }
 
-        Ok(files)
+        Ok((files, skipped))
     }
 
     fn convert_ee_symbol_to_ki(
         &self,
-        ee_symbol: &EeSymbol,
+        ee_units: &[EeSymbol],
         component_name: &str,
         manufacturer: &str,
         datasheet: &str,
@@ -211,6 +520,35 @@ impl ComponentConverter {
     ) -> Result<KiSymbol> {
         let converter = Converter::new(self.kicad_version);
 
+        let mut symbol = KiSymbol {
+            name: component_name.to_string(),
+            reference: ee_units.first().map_or_else(|| "U".to_string(), |unit| unit.prefix.clone()),
+            value: component_name.to_string(),
+            footprint: String::new(),
+            fp_filters: Vec::new(),
+            datasheet: datasheet.to_string(),
+            manufacturer: manufacturer.to_string(),
+            lcsc_id: lcsc_id.to_string(),
+            jlc_id: jlc_id.to_string(),
+            unit_count: ee_units.len().max(1) as u32,
+            pins: Vec::new(),
+            rectangles: Vec::new(),
+            circles: Vec::new(),
+            arcs: Vec::new(),
+            polylines: Vec::new(),
+            texts: Vec::new(),
+        };
+
+        // Units are numbered from 1 in the order EasyEDA lists the sub-parts
+        for (index, ee_symbol) in ee_units.iter().enumerate() {
+            Self::convert_symbol_unit(&converter, ee_symbol, index as u32 + 1, &mut symbol);
+        }
+
+        Ok(symbol)
+    }
+
+    /// Convert the shapes of one EasyEDA (sub-)symbol into items of the given unit
+    fn convert_symbol_unit(converter: &Converter, ee_symbol: &EeSymbol, unit: u32, symbol: &mut KiSymbol) {
         // Convert pins
         let pins: Vec<KiPin> = ee_symbol.pins.iter().map(|ee_pin| {
             let pin_type = PinType::from_easyeda(&ee_pin.electric_type);
@@ -231,6 +569,7 @@ impl ComponentConverter {
                 pos_y: converter.flip_y(ee_pin.y),
                 rotation: ee_pin.rotation,
                 length: ee_pin.length,
+                unit,
             }
         }).collect();
 
@@ -243,22 +582,24 @@ impl ComponentConverter {
                 y2: converter.flip_y(rect.y + rect.height),
                 stroke_width: rect.stroke_width,
                 fill: rect.fill,
+                unit,
             }
         }).collect();
 
         // Convert circles
-        let circles: Vec<KiCircle> = ee_symbol.circles.iter().map(|circle| {
+        let mut circles: Vec<KiCircle> = ee_symbol.circles.iter().map(|circle| {
             KiCircle {
                 cx: circle.cx,
                 cy: converter.flip_y(circle.cy),
                 radius: circle.radius,
                 stroke_width: circle.stroke_width,
                 fill: circle.fill,
+                unit,
             }
         }).collect();
 
         // Convert polylines
-        let polylines: Vec<KiPolyline> = ee_symbol.polylines.iter().map(|polyline| {
+        let mut polylines: Vec<KiPolyline> = ee_symbol.polylines.iter().map(|polyline| {
             let points: Vec<(f64, f64)> = polyline.points.iter()
                 .map(|(x, y)| (*x, converter.flip_y(*y)))
                 .collect();
@@ -267,134 +608,448 @@ impl ComponentConverter {
                 points,
                 stroke_width: polyline.stroke_width,
                 fill: false,
+                unit,
             }
         }).collect();
 
-        // For now, skip arcs and other complex shapes
-        let arcs = Vec::new();
+        // Convert polygons to closed polylines
+        for polygon in &ee_symbol.polygons {
+            let mut points: Vec<(f64, f64)> = polygon.points.iter()
+                .map(|(x, y)| (*x, converter.flip_y(*y)))
+                .collect();
+            if points.len() > 2 && points.first() != points.last() {
+                points.push(points[0]);
+            }
 
-        Ok(KiSymbol {
-            name: component_name.to_string(),
-            reference: ee_symbol.prefix.clone(),
-            value: component_name.to_string(),
-            footprint: String::new(),
-            datasheet: datasheet.to_string(),
-            manufacturer: manufacturer.to_string(),
-            lcsc_id: lcsc_id.to_string(),
-            jlc_id: jlc_id.to_string(),
-            pins,
-            rectangles,
-            circles,
-            arcs,
-            polylines,
-        })
+            polylines.push(KiPolyline {
+                points,
+                stroke_width: polygon.stroke_width,
+                fill: polygon.fill,
+                unit,
+            });
+        }
+
+        // Convert ellipses: true circles stay circles, others become closed polylines
+        for ellipse in &ee_symbol.ellipses {
+            if (ellipse.rx - ellipse.ry).abs() < 1e-6 {
+                circles.push(KiCircle {
+                    cx: ellipse.cx,
+                    cy: converter.flip_y(ellipse.cy),
+                    radius: ellipse.rx,
+                    stroke_width: ellipse.stroke_width,
+                    fill: ellipse.fill,
+                    unit,
+                });
+                continue;
+            }
+
+            let points = (0..=ELLIPSE_SEGMENTS).map(|i| {
+                let t = 2.0 * std::f64::consts::PI * i as f64 / ELLIPSE_SEGMENTS as f64;
+                (ellipse.cx + ellipse.rx * t.cos(), converter.flip_y(ellipse.cy + ellipse.ry * t.sin()))
+            }).collect();
+
+            polylines.push(KiPolyline {
+                points,
+                stroke_width: ellipse.stroke_width,
+                fill: ellipse.fill,
+                unit,
+            });
+        }
+
+        // Convert arcs from center/angles to start/mid/end points
+        let mut arcs: Vec<KiArc> = ee_symbol.arcs.iter().map(|arc| {
+            let [(start_x, start_y), (mid_x, mid_y), (end_x, end_y)] = arc_points(converter, arc);
+
+            KiArc {
+                start_x,
+                start_y: converter.flip_y(start_y),
+                mid_x,
+                mid_y: converter.flip_y(mid_y),
+                end_x,
+                end_y: converter.flip_y(end_y),
+                stroke_width: arc.stroke_width,
+                unit,
+            }
+        }).collect();
+
+        // Convert SVG paths into polylines and arcs
+        for path in &ee_symbol.paths {
+            match Self::convert_svg_path(converter, path, unit) {
+                Ok((path_polylines, path_arcs)) => {
+                    polylines.extend(path_polylines);
+                    arcs.extend(path_arcs);
+                }
+                Err(e) => log::warn!("Failed to convert path '{}': {}", path.path_data, e),
+            }
+        }
+
+        // Convert texts
+        let texts: Vec<SymbolKiText> = ee_symbol.texts.iter().map(|text| {
+            SymbolKiText {
+                text: text.text.clone(),
+                pos_x: text.x,
+                pos_y: converter.flip_y(text.y),
+                rotation: text.rotation,
+                font_size: text.font_size,
+                unit,
+            }
+        }).collect();
+
+        symbol.pins.extend(pins);
+        symbol.rectangles.extend(rectangles);
+        symbol.circles.extend(circles);
+        symbol.arcs.extend(arcs);
+        symbol.polylines.extend(polylines);
+        symbol.texts.extend(texts);
+    }
+
+    /// Split an SVG path into polylines (one per straight run) and arcs
+    fn convert_svg_path(converter: &Converter, path: &EePath, unit: u32) -> Result<(Vec<KiPolyline>, Vec<KiArc>)> {
+        let mut polylines = Vec::new();
+        let mut arcs = Vec::new();
+        let mut current: Vec<(f64, f64)> = Vec::new();
+        let mut subpath_start = (0.0, 0.0);
+        let mut pos = (0.0, 0.0);
+
+        let flush = |points: &mut Vec<(f64, f64)>, polylines: &mut Vec<KiPolyline>| {
+            if points.len() > 1 {
+                polylines.push(KiPolyline {
+                    points: points.iter().map(|(x, y)| (*x, converter.flip_y(*y))).collect(),
+                    stroke_width: path.stroke_width,
+                    fill: path.fill,
+                    unit,
+                });
+            }
+            points.clear();
+        };
+
+        for cmd in parse_svg_path(&path.path_data)? {
+            match cmd {
+                SvgCommand::MoveTo { x, y } => {
+                    flush(&mut current, &mut polylines);
+                    pos = (x, y);
+                    subpath_start = pos;
+                    current.push(pos);
+                }
+                SvgCommand::LineTo { x, y } => {
+                    if current.is_empty() {
+                        current.push(pos);
+                    }
+                    pos = (x, y);
+                    current.push(pos);
+                }
+                SvgCommand::Arc { rx, ry, angle, large_arc, sweep, x, y } => {
+                    flush(&mut current, &mut polylines);
+
+                    let (cx, cy, start_angle, end_angle) =
+                        converter.compute_arc_center(pos, (x, y), (rx, ry), angle, large_arc, sweep)?;
+                    let radius = (pos.0 - cx).hypot(pos.1 - cy);
+
+                    // Mid angle along the sweep direction
+                    let mut extent = end_angle - start_angle;
+                    if sweep && extent < 0.0 {
+                        extent += 360.0;
+                    } else if !sweep && extent > 0.0 {
+                        extent -= 360.0;
+                    }
+                    let mid = converter.deg_to_rad(start_angle + extent / 2.0);
+
+                    arcs.push(KiArc {
+                        start_x: pos.0,
+                        start_y: converter.flip_y(pos.1),
+                        mid_x: cx + radius * mid.cos(),
+                        mid_y: converter.flip_y(cy + radius * mid.sin()),
+                        end_x: x,
+                        end_y: converter.flip_y(y),
+                        stroke_width: path.stroke_width,
+                        unit,
+                    });
+
+                    pos = (x, y);
+                    current.push(pos);
+                }
+                SvgCommand::ClosePath => {
+                    if current.is_empty() {
+                        current.push(pos);
+                    }
+                    current.push(subpath_start);
+                    pos = subpath_start;
+                }
+            }
+        }
+        flush(&mut current, &mut polylines);
+
+        Ok((polylines, arcs))
     }
 
     fn convert_ee_footprint_to_ki(
         &self,
         ee_footprint: &EeFootprint,
         component_name: &str,
+        model_file: Option<&str>,
     ) -> Result<KiFootprint> {
         let converter = Converter::new(self.kicad_version);
 
         // Convert pads
-        let pads: Vec<KiPad> = ee_footprint.pads.iter().map(|ee_pad| {
-            let pad_type = if ee_pad.hole_radius.is_some() {
-                PadType::ThroughHole
-            } else {
-                PadType::Smd
+        let mut pads: Vec<KiPad> = ee_footprint.pads.iter().map(|ee_pad| {
+            // A hole without copper around it (unplated, or no larger than
+            // the drill itself) is a mechanical hole
+            let pad_type = match ee_pad.hole_radius {
+                Some(radius) if !ee_pad.plated
+                    || (ee_pad.width <= radius * 2.0 && ee_pad.height <= radius * 2.0) => PadType::NpThroughHole,
+                Some(_) => PadType::ThroughHole,
+                None => PadType::Smd,
             };
 
-            let shape = PadShape::from_easyeda(&ee_pad.shape);
+            let layers = match pad_type {
+                PadType::NpThroughHole => vec!["*.Cu".to_string(), "*.Mask".to_string()],
+                PadType::ThroughHole => map_pad_layers_tht(ee_pad.layer_id),
+                _ => map_pad_layers_smd(ee_pad.layer_id),
+            };
+
+            let mut shape = PadShape::from_easyeda(&ee_pad.shape);
 
             let drill = ee_pad.hole_radius.map(|radius| {
-                Drill {
-                    diameter: radius * 2.0,  // Convert radius to diameter
-                    width: None,  // No oval drills for now
-                    offset_x: 0.0,
-                    offset_y: 0.0,
+                let diameter = radius * 2.0;  // Convert radius to diameter
+                match ee_pad.hole_length {
+                    // Slotted hole, running along the pad's long side
+                    Some(length) if length > diameter => {
+                        let (size_x, size_y) = if ee_pad.width >= ee_pad.height {
+                            (length, diameter)
+                        } else {
+                            (diameter, length)
+                        };
+                        Drill { diameter: size_x, width: Some(size_y), offset_x: 0.0, offset_y: 0.0 }
+                    }
+                    _ => Drill { diameter, width: None, offset_x: 0.0, offset_y: 0.0 },
                 }
             });
 
+            // Polygon pads carry their outline in absolute, already rotated
+            // coordinates; make it relative to the pad anchor
+            let mut rotation = ee_pad.rotation;
+            let mut polygon = None;
+            let (mut size_x, mut size_y) = (ee_pad.width, ee_pad.height);
+            if shape == PadShape::Custom {
+                let coords: Vec<f64> = ee_pad.points
+                    .split_whitespace()
+                    .filter_map(|s| s.parse().ok())
+                    .collect();
+                if coords.len() >= 6 {
+                    polygon = Some(coords.chunks_exact(2)
+                        .map(|p| (p[0] - ee_pad.x, p[1] - ee_pad.y))
+                        .collect());
+                    rotation = 0.0;
+                    size_x = CUSTOM_PAD_ANCHOR;
+                    size_y = CUSTOM_PAD_ANCHOR;
+                } else {
+                    log::warn!("Polygon pad {} has no outline, exporting as rect", ee_pad.number);
+                    shape = PadShape::Rect;
+                }
+            }
+
+            // KiCad rejects pads smaller than their drill
+            let min_size = match pad_type {
+                PadType::NpThroughHole => ee_pad.hole_radius.unwrap_or(0.0) * 2.0,
+                _ => 0.0,
+            };
+
+            // Coordinates stay in EasyEDA units; the exporter converts to mm.
+            // Both the EasyEDA PCB canvas and KiCad footprints point Y down.
             KiPad {
                 number: ee_pad.number.clone(),
                 pad_type,
                 shape,
-                pos_x: converter.px_to_mm(ee_pad.x),
-                pos_y: converter.px_to_mm(converter.flip_y(ee_pad.y)),
-                size_x: converter.px_to_mm(ee_pad.width),
-                size_y: converter.px_to_mm(ee_pad.height),
-                rotation: ee_pad.rotation,
+                pos_x: ee_pad.x,
+                pos_y: ee_pad.y,
+                size_x: size_x.max(min_size),
+                size_y: size_y.max(min_size),
+                rotation,
                 drill,
-                layers: vec!["F.Cu".to_string(), "F.Paste".to_string(), "F.Mask".to_string()],
-                polygon: None,  // No custom polygons for now
+                layers,
+                polygon,
             }
         }).collect();
 
-        // Convert tracks to lines
-        let lines: Vec<KiLine> = ee_footprint.tracks.iter().filter_map(|track| {
+        // Convert tracks to lines, one per segment, on their own layer
+        let mut lines: Vec<KiLine> = Vec::new();
+        for track in &ee_footprint.tracks {
+            let Some(layer) = map_graphic_layer(track.layer_id) else {
+                continue;
+            };
+
             let coords: Vec<f64> = track.points
                 .split_whitespace()
                 .filter_map(|s| s.parse().ok())
                 .collect();
+            let points: Vec<(f64, f64)> = coords.chunks_exact(2).map(|p| (p[0], p[1])).collect();
 
-            if coords.len() >= 4 {
-                Some(KiLine {
-                    start_x: converter.px_to_mm(coords[0]),
-                    start_y: converter.px_to_mm(converter.flip_y(coords[1])),
-                    end_x: converter.px_to_mm(coords[2]),
-                    end_y: converter.px_to_mm(converter.flip_y(coords[3])),
-                    width: converter.px_to_mm(track.stroke_width),
-                    layer: "F.SilkS".to_string(),
-                })
-            } else {
-                None
+            for segment in points.windows(2) {
+                lines.push(KiLine {
+                    start_x: segment[0].0,
+                    start_y: segment[0].1,
+                    end_x: segment[1].0,
+                    end_y: segment[1].1,
+                    width: track.stroke_width,
+                    layer: layer.clone(),
+                });
             }
-        }).collect();
+        }
 
         // Convert circles
-        let circles: Vec<FootprintKiCircle> = ee_footprint.circles.iter().map(|circle| {
-            let center_x = converter.px_to_mm(circle.cx);
-            let center_y = converter.px_to_mm(converter.flip_y(circle.cy));
-            let radius_mm = converter.px_to_mm(circle.radius);
+        let circles: Vec<FootprintKiCircle> = ee_footprint.circles.iter().filter_map(|circle| {
+            let layer = map_graphic_layer(circle.layer_id)?;
+            let center_x = circle.cx;
+            let center_y = circle.cy;
 
             // KiCad represents circles with center and end point (on the circle)
-            FootprintKiCircle {
+            Some(FootprintKiCircle {
                 center_x,
                 center_y,
-                end_x: center_x + radius_mm,  // Point on circle (radius to the right)
+                end_x: center_x + circle.radius,  // Point on circle (radius to the right)
                 end_y: center_y,
-                width: converter.px_to_mm(circle.stroke_width),
-                layer: "F.SilkS".to_string(),
+                width: circle.stroke_width,
+                layer,
                 fill: circle.fill,
-            }
+            })
         }).collect();
 
-        // Reference and value texts
+        // Convert arcs
+        let arcs: Vec<FootprintKiArc> = ee_footprint.arcs.iter().filter_map(|arc| {
+            let layer = map_graphic_layer(arc.layer_id)?;
+            let [(start_x, start_y), (mid_x, mid_y), (end_x, end_y)] = arc_points(&converter, arc);
+
+            Some(FootprintKiArc {
+                start_x,
+                start_y,
+                mid_x,
+                mid_y,
+                end_x,
+                end_y,
+                width: arc.stroke_width,
+                layer,
+            })
+        }).collect();
+
+        // Convert rectangles
+        let rectangles: Vec<FootprintKiRectangle> = ee_footprint.rectangles.iter().filter_map(|rect| {
+            let layer = map_graphic_layer(rect.layer_id)?;
+
+            Some(FootprintKiRectangle {
+                start_x: rect.x,
+                start_y: rect.y,
+                end_x: rect.x + rect.width,
+                end_y: rect.y + rect.height,
+                width: rect.stroke_width,
+                layer,
+                fill: rect.fill,
+            })
+        }).collect();
+
+        // Convert solid regions: copper/graphic fills, keepouts and board cutouts
+        let mut polygons: Vec<KiPolygon> = Vec::new();
+        let mut zones: Vec<KiZone> = Vec::new();
+        for region in &ee_footprint.solid_regions {
+            let points = match path_outline(&region.path) {
+                Ok(points) if points.len() >= 3 => points,
+                Ok(_) => continue,
+                Err(e) => {
+                    log::warn!("Failed to convert solid region '{}': {}", region.path, e);
+                    continue;
+                }
+            };
+
+            match region.region_type.as_str() {
+                "cutout" => zones.push(KiZone {
+                    points,
+                    layers: vec!["F.Cu".to_string(), "B.Cu".to_string()],
+                }),
+                "npth" => polygons.push(KiPolygon {
+                    points,
+                    width: 1.0,
+                    layer: "Edge.Cuts".to_string(),
+                    fill: false,
+                }),
+                _ => {
+                    if let Some(layer) = map_graphic_layer(region.layer_id) {
+                        polygons.push(KiPolygon {
+                            points,
+                            width: 0.0,
+                            layer,
+                            fill: true,
+                        });
+                    }
+                }
+            }
+        }
+
+        // Mounting holes become NPTH pads, vias small plated pads
+        for hole in &ee_footprint.holes {
+            pads.push(KiPad {
+                number: String::new(),
+                pad_type: PadType::NpThroughHole,
+                shape: PadShape::Circle,
+                pos_x: hole.x,
+                pos_y: hole.y,
+                size_x: hole.radius * 2.0,
+                size_y: hole.radius * 2.0,
+                rotation: 0.0,
+                drill: Some(Drill {
+                    diameter: hole.radius * 2.0,
+                    width: None,
+                    offset_x: 0.0,
+                    offset_y: 0.0,
+                }),
+                layers: vec!["*.Cu".to_string(), "*.Mask".to_string()],
+                polygon: None,
+            });
+        }
+        for via in &ee_footprint.vias {
+            pads.push(KiPad {
+                number: String::new(),
+                pad_type: PadType::ThroughHole,
+                shape: PadShape::Circle,
+                pos_x: via.x,
+                pos_y: via.y,
+                size_x: via.diameter,
+                size_y: via.diameter,
+                rotation: 0.0,
+                drill: Some(Drill {
+                    diameter: via.radius * 2.0,
+                    width: None,
+                    offset_x: 0.0,
+                    offset_y: 0.0,
+                }),
+                layers: vec!["*.Cu".to_string()],
+                polygon: None,
+            });
+        }
+
+        // Reference and value texts: 1 mm high, 3 mm above and below the origin
         let texts = vec![
             KiText {
                 text: "REF**".to_string(),
                 pos_x: 0.0,
-                pos_y: -3.0,
+                pos_y: -3.0 * PX_PER_MM,
                 rotation: 0.0,
                 layer: "F.SilkS".to_string(),
-                size: 1.0,
-                thickness: 0.15,
+                size: PX_PER_MM,
+                thickness: 0.15 * PX_PER_MM,
             },
             KiText {
                 text: component_name.to_string(),
                 pos_x: 0.0,
-                pos_y: 3.0,
+                pos_y: 3.0 * PX_PER_MM,
                 rotation: 0.0,
                 layer: "F.Fab".to_string(),
-                size: 1.0,
-                thickness: 0.15,
+                size: PX_PER_MM,
+                thickness: 0.15 * PX_PER_MM,
             },
         ];
 
-        // 3D model reference (if exists)
-        let model_3d = Some(Ki3dModel {
-            path: format!("${{KIPRJMOD}}/nlbn.3dshapes/{}.wrl", component_name),
+        // 3D model reference (if a model was produced)
+        let model_3d = model_file.map(|model_file| Ki3dModel {
+            path: self.model_reference(model_file),
             offset: (0.0, 0.0, 0.0),
             scale: (1.0, 1.0, 1.0),
             rotate: (0.0, 0.0, 0.0),
@@ -406,13 +1061,46 @@ impl ComponentConverter {
             tracks: Vec::new(),
             lines,
             circles,
-            arcs: Vec::new(),
+            arcs,
+            rectangles,
+            polygons,
+            zones,
             texts,
             model_3d,
         })
     }
 }
 
+/// Start, mid and end points of a center-based arc, in EasyEDA coordinates
+fn arc_points(converter: &Converter, arc: &EeArc) -> [(f64, f64); 3] {
+    let point_at = |angle: f64| {
+        let rad = converter.deg_to_rad(angle);
+        (arc.x + arc.radius * rad.cos(), arc.y + arc.radius * rad.sin())
+    };
+
+    [
+        point_at(arc.start_angle),
+        point_at((arc.start_angle + arc.end_angle) / 2.0),
+        point_at(arc.end_angle),
+    ]
+}
+
+/// Outline points of an SVG path (arcs are reduced to their end points)
+fn path_outline(path: &str) -> Result<Vec<(f64, f64)>> {
+    let mut points = Vec::new();
+
+    for cmd in parse_svg_path(path)? {
+        match cmd {
+            SvgCommand::MoveTo { x, y } | SvgCommand::LineTo { x, y } | SvgCommand::Arc { x, y, .. } => {
+                points.push((x, y));
+            }
+            SvgCommand::ClosePath => {}
+        }
+    }
+
+    Ok(points)
+}
+
 /// Result of component conversion
 #[derive(Debug, Clone)]
 pub struct ConversionResult {
@@ -423,8 +1111,27 @@ pub struct ConversionResult {
     pub files_created: Vec<String>,
 }
 
+/// Footprint filters for a symbol: the package family (`SOIC*8*` for
+/// `SOIC-8_L4.9-W3.9-P1.27`) and the linked footprint itself
+fn footprint_filters(package_name: &str, footprint: Option<&str>) -> Vec<String> {
+    let mut filters = Vec::new();
+
+    let family = package_name.split('_').next().unwrap_or_default().trim();
+    if !family.is_empty() {
+        filters.push(format!("{}*", family.replace(['-', ' '], "*")));
+    }
+
+    if let Some(name) = footprint.and_then(|f| f.split(':').next_back()) {
+        if !filters.iter().any(|f| f == name) {
+            filters.push(name.to_string());
+        }
+    }
+
+    filters
+}
+
 /// Sanitize component name for file system
-fn sanitize_component_name(name: &str) -> String {
+pub fn sanitize_component_name(name: &str) -> String {
     name.chars()
         .map(|c| {
             if c.is_alphanumeric() || c == '-' || c == '_' {
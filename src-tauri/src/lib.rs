@@ -1,12 +1,18 @@
+#[cfg(feature = "gui")]
 mod commands;
+#[cfg(feature = "gui")]
 mod state;
-mod types;
+#[cfg(feature = "gui")]
 mod history;
-mod nlbn;
-mod converter_impl;
+pub mod types;
+pub mod nlbn;
+pub mod converter_impl;
+pub mod batch;
 
+#[cfg(feature = "gui")]
 use state::AppState;
 
+#[cfg(feature = "gui")]
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     // Initialize logger
@@ -27,9 +33,12 @@ pub fn run() {
             commands::convert_component,
             commands::batch_convert,
             commands::select_output_directory,
+            commands::select_project_directory,
             commands::get_history,
             commands::clear_history,
             commands::import_ids_from_file,
+            commands::get_cache_info,
+            commands::clear_cache,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
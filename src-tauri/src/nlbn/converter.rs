@@ -148,6 +148,31 @@ impl Converter {
         Ok((cx, cy, theta1, theta2))
     }
 
+    /// Center and radius of the circle through an arc's start, mid and end points
+    pub fn arc_center_from_points(
+        &self,
+        start: (f64, f64),
+        mid: (f64, f64),
+        end: (f64, f64),
+    ) -> Option<(f64, f64, f64)> {
+        let (ax, ay) = start;
+        let (bx, by) = mid;
+        let (cx, cy) = end;
+
+        let d = 2.0 * (ax * (by - cy) + bx * (cy - ay) + cx * (ay - by));
+        if d.abs() < 1e-10 {
+            return None; // Collinear points
+        }
+
+        let a2 = ax * ax + ay * ay;
+        let b2 = bx * bx + by * by;
+        let c2 = cx * cx + cy * cy;
+        let ux = (a2 * (by - cy) + b2 * (cy - ay) + c2 * (ay - by)) / d;
+        let uy = (a2 * (cx - bx) + b2 * (ax - cx) + c2 * (bx - ax)) / d;
+
+        Some((ux, uy, (ax - ux).hypot(ay - uy)))
+    }
+
     /// Calculate bounding box for a set of points
     pub fn calculate_bbox(&self, points: &[(f64, f64)]) -> Option<(f64, f64, f64, f64)> {
         if points.is_empty() {
@@ -195,6 +220,15 @@ mod tests {
         assert_eq!(converter.flip_y(-5.0), 5.0);
     }
 
+    #[test]
+    fn test_arc_center_from_points() {
+        let converter = Converter::new(KicadVersion::V6);
+        let (cx, cy, r) = converter.arc_center_from_points((1.0, 0.0), (0.0, 1.0), (-1.0, 0.0)).unwrap();
+        assert!(cx.abs() < 1e-9 && cy.abs() < 1e-9);
+        assert!((r - 1.0).abs() < 1e-9);
+        assert!(converter.arc_center_from_points((0.0, 0.0), (1.0, 1.0), (2.0, 2.0)).is_none());
+    }
+
     #[test]
     fn test_deg_to_rad() {
         let converter = Converter::new(KicadVersion::V6);
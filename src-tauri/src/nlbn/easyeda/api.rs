@@ -2,6 +2,7 @@ use reqwest::Client;
 use super::super::error::{EasyedaError, Result};
 use super::models::{ComponentData, ApiResponse, Model3dInfo};
 
+#[derive(Clone)]
 pub struct EasyedaApi {
     client: Client,
 }
@@ -17,6 +18,12 @@ impl EasyedaApi {
     }
 
     pub async fn get_component_data(&self, lcsc_id: &str) -> Result<ComponentData> {
+        let body = self.fetch_component_response(lcsc_id).await?;
+        Self::parse_component_response(lcsc_id, &body)
+    }
+
+    /// Fetch the raw `/api/products/{id}/components` response body
+    pub async fn fetch_component_response(&self, lcsc_id: &str) -> Result<Vec<u8>> {
         let url = format!(
             "https://easyeda.com/api/products/{}/components?version=6.4.19.5",
             lcsc_id
@@ -34,8 +41,16 @@ impl EasyedaApi {
             return Err(EasyedaError::ComponentNotFound(lcsc_id.to_string()).into());
         }
 
-        let api_response: ApiResponse = response.json()
+        let body = response.bytes()
             .await
+            .map_err(EasyedaError::ApiRequest)?;
+
+        Ok(body.to_vec())
+    }
+
+    /// Parse a raw `/api/products/{id}/components` response body
+    pub fn parse_component_response(lcsc_id: &str, body: &[u8]) -> Result<ComponentData> {
+        let api_response: ApiResponse = serde_json::from_slice(body)
             .map_err(|e| EasyedaError::InvalidData(format!("Failed to parse JSON: {}", e)))?;
 
         if !api_response.success {
@@ -45,8 +60,20 @@ impl EasyedaApi {
         let result = api_response.result
             .ok_or_else(|| EasyedaError::InvalidData("Missing result field".to_string()))?;
 
+        // Sub-parts of multi-unit parts (quad op-amps, dual MOSFETs, ...)
+        let sub_parts: Vec<Vec<String>> = result.subparts.as_deref()
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|part| part.get("dataStr"))
+            .map(Self::extract_shapes)
+            .collect();
+
+        log::debug!("Found {} sub-parts", sub_parts.len());
+
         // Parse dataStr - it's an object with a "shape" array
+        // (multi-unit parts may only carry it per sub-part)
         let data_str_obj = result.data_str.as_ref()
+            .or_else(|| result.subparts.as_ref()?.first()?.get("dataStr"))
             .ok_or_else(|| EasyedaError::InvalidData("Missing dataStr field".to_string()))?;
 
         log::debug!("data_str_obj type: {:?}", data_str_obj);
@@ -63,17 +90,7 @@ impl EasyedaApi {
 
         log::debug!("Extracted bbox: x={}, y={}", bbox_x, bbox_y);
 
-        let data_str = if let Some(shape_array) = data_str_obj.get("shape").and_then(|v| v.as_array()) {
-            // Extract shape array as Vec<String>
-            log::debug!("Found shape array with {} elements", shape_array.len());
-            shape_array.iter()
-                .filter_map(|v| v.as_str().map(|s| s.to_string()))
-                .collect()
-        } else {
-            // Fallback: empty vector
-            log::warn!("data_str_obj doesn't have shape array");
-            vec![]
-        };
+        let data_str = Self::extract_shapes(data_str_obj);
 
         log::debug!("Final data_str has {} shapes", data_str.len());
 
@@ -94,6 +111,15 @@ impl EasyedaApi {
             .unwrap_or("")
             .to_string();
 
+        // Sub-parts carry "U?.1"-style prefixes
+        let prefix = data_str_obj.get("head")
+            .and_then(|h| h.get("c_para"))
+            .and_then(|cp| cp.get("pre"))
+            .and_then(|v| v.as_str())
+            .and_then(|pre| pre.split(['?', '.']).next())
+            .unwrap_or("")
+            .to_string();
+
         let jlc_id = data_str_obj.get("head")
             .and_then(|h| h.get("c_para"))
             .and_then(|cp| cp.get("BOM_JLCPCB Part Class"))
@@ -105,7 +131,12 @@ impl EasyedaApi {
                    manufacturer, datasheet, jlc_id);
 
         // Parse package_detail - it's an object with a "dataStr" that has a "shape" array
-        let (package_detail, package_bbox_x, package_bbox_y, model_3d) = if let Some(pkg) = result.package_detail {
+        let (package_detail, package_name, package_bbox_x, package_bbox_y, model_3d) = if let Some(pkg) = result.package_detail {
+            let package_name = pkg.get("title")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string();
+
             // Extract bbox from package head
             let pkg_bbox_x = pkg.get("dataStr")
                 .and_then(|ds| ds.get("head"))
@@ -142,18 +173,21 @@ impl EasyedaApi {
             // Extract 3D model info from SVGNODE in shapes array
             let model_3d = Self::extract_3d_model_from_svgnode(&shapes);
 
-            (shapes, pkg_bbox_x, pkg_bbox_y, model_3d)
+            (shapes, package_name, pkg_bbox_x, pkg_bbox_y, model_3d)
         } else {
-            (vec![], 0.0, 0.0, None)
+            (vec![], String::new(), 0.0, 0.0, None)
         };
 
         Ok(ComponentData {
             lcsc_id: lcsc_id.to_string(),
             title,
+            prefix,
             data_str,
+            sub_parts,
             bbox_x,
             bbox_y,
             package_detail,
+            package_name,
             package_bbox_x,
             package_bbox_y,
             model_3d,
@@ -163,6 +197,20 @@ impl EasyedaApi {
         })
     }
 
+    /// Extract the "shape" array of a dataStr object as strings
+    fn extract_shapes(data_str_obj: &serde_json::Value) -> Vec<String> {
+        if let Some(shape_array) = data_str_obj.get("shape").and_then(|v| v.as_array()) {
+            log::debug!("Found shape array with {} elements", shape_array.len());
+            shape_array.iter()
+                .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                .collect()
+        } else {
+            // Fallback: empty vector
+            log::warn!("dataStr doesn't have shape array");
+            vec![]
+        }
+    }
+
     fn extract_3d_model_from_svgnode(shapes: &[String]) -> Option<Model3dInfo> {
         // Look for SVGNODE in shapes array
         for shape in shapes {
@@ -182,8 +230,27 @@ impl EasyedaApi {
                                         .and_then(|v| v.as_str())
                                         .map(|s| s.to_string());
 
+                                    // Placement: "x,y" canvas position, height and "x,y,z" rotation
+                                    let numbers = |key: &str| -> Vec<f64> {
+                                        attrs.get(key)
+                                            .and_then(|v| v.as_str())
+                                            .map(|s| s.split(',').filter_map(|n| n.trim().parse().ok()).collect())
+                                            .unwrap_or_default()
+                                    };
+                                    let origin = match numbers("c_origin")[..] {
+                                        [x, y] => Some((x, y)),
+                                        _ => None,
+                                    };
+                                    let z = attrs.get("z")
+                                        .and_then(|v| v.as_f64().or_else(|| v.as_str()?.trim().parse().ok()))
+                                        .unwrap_or(0.0);
+                                    let rotation = match numbers("c_rotation")[..] {
+                                        [x, y, z] => (x, y, z),
+                                        _ => (0.0, 0.0, 0.0),
+                                    };
+
                                     if let (Some(uuid), Some(title)) = (uuid, title) {
-                                        return Some(Model3dInfo { uuid, title });
+                                        return Some(Model3dInfo { uuid, title, origin, z, rotation });
                                     }
                                 }
                             }
@@ -245,3 +312,35 @@ impl Default for EasyedaApi {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_3d_model_placement() {
+        let shapes = vec![
+            "TRACK~1~3~~4000 3000 4010 3000~gge1~0".to_string(),
+            r#"SVGNODE~{"gId":"g1","nodeName":"g","nodeType":1,"layerid":"19","attrs":{"c_width":"19.6","c_height":"15.7","c_rotation":"0,0,90","z":"-1.5","c_origin":"4001.2,2999.8","uuid":"abc123","title":"SOIC-8","layerid":"19","c_etype":"outline3D"},"childNodes":[]}"#.to_string(),
+        ];
+
+        let model = EasyedaApi::extract_3d_model_from_svgnode(&shapes).unwrap();
+        assert_eq!(model.uuid, "abc123");
+        assert_eq!(model.origin, Some((4001.2, 2999.8)));
+        assert_eq!(model.z, -1.5);
+        assert_eq!(model.rotation, (0.0, 0.0, 90.0));
+    }
+
+    #[test]
+    fn test_reference_prefix() {
+        let response = |pre: &str| format!(
+            r#"{{"success":true,"result":{{"title":"T","dataStr":{{"head":{{"x":0,"y":0,"c_para":{{"pre":"{}"}}}},"shape":[]}}}}}}"#,
+            pre
+        );
+
+        let prefix = |pre: &str| EasyedaApi::parse_component_response("C1", response(pre).as_bytes()).unwrap().prefix;
+        assert_eq!(prefix("D?"), "D");
+        assert_eq!(prefix("U?.1"), "U");
+        assert_eq!(prefix(""), "");
+    }
+}
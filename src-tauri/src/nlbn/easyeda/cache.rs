@@ -0,0 +1,354 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, SystemTime};
+use super::super::error::{KicadError, Result};
+use super::api::EasyedaApi;
+use super::source::ComponentSource;
+
+/// Default time-to-live of cached entries (7 days)
+pub const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Default maximum total size of the cache (512 MiB)
+pub const DEFAULT_CACHE_MAX_SIZE: u64 = 512 * 1024 * 1024;
+
+static TMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Kind of cached content, each stored in its own subdirectory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheKind {
+    /// Raw `/api/products/{id}/components` response, keyed by LCSC ID
+    Component,
+    /// OBJ model, keyed by model UUID
+    ModelObj,
+    /// STEP model, keyed by model UUID
+    ModelStep,
+}
+
+impl CacheKind {
+    fn subdir(&self) -> &'static str {
+        match self {
+            CacheKind::Component => "components",
+            CacheKind::ModelObj | CacheKind::ModelStep => "models",
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            CacheKind::Component => "json",
+            CacheKind::ModelObj => "obj",
+            CacheKind::ModelStep => "step",
+        }
+    }
+}
+
+/// Summary of the cache contents
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CacheStats {
+    pub components: usize,
+    pub models: usize,
+    pub total_bytes: u64,
+}
+
+/// Persistent content cache of EasyEDA responses and 3D models
+///
+/// Entries are plain files under `<dir>/components` and `<dir>/models`; the
+/// file modification time is used for TTL checks and eviction order.
+#[derive(Debug, Clone)]
+pub struct ResponseCache {
+    dir: PathBuf,
+    ttl: Duration,
+    max_size: u64,
+}
+
+impl ResponseCache {
+    pub fn new(dir: &Path) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+            ttl: DEFAULT_CACHE_TTL,
+            max_size: DEFAULT_CACHE_MAX_SIZE,
+        }
+    }
+
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    pub fn with_max_size(mut self, max_size: u64) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn entry_path(&self, kind: CacheKind, key: &str) -> PathBuf {
+        let key: String = key.chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+            .collect();
+
+        self.dir.join(kind.subdir()).join(format!("{}.{}", key, kind.extension()))
+    }
+
+    /// Get a cached entry if it exists and has not expired
+    pub fn get(&self, kind: CacheKind, key: &str) -> Option<Vec<u8>> {
+        let path = self.entry_path(kind, key);
+        let age = fs::metadata(&path).ok()?
+            .modified().ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .unwrap_or_default();
+
+        if age > self.ttl {
+            log::debug!("Cache entry expired: {}", path.display());
+            return None;
+        }
+
+        fs::read(&path).ok()
+    }
+
+    /// Get a cached entry regardless of its age (used as an offline fallback)
+    pub fn get_stale(&self, kind: CacheKind, key: &str) -> Option<Vec<u8>> {
+        fs::read(self.entry_path(kind, key)).ok()
+    }
+
+    /// Store an entry, then evict the oldest entries if the size limit is exceeded
+    pub fn put(&self, kind: CacheKind, key: &str, data: &[u8]) -> Result<()> {
+        let path = self.entry_path(kind, key);
+        let parent = path.parent().unwrap_or(&self.dir);
+        fs::create_dir_all(parent)
+            .map_err(KicadError::Io)?;
+
+        // Write to a unique temp file and rename so concurrent readers never see partial data
+        let tmp_path = parent.join(format!(
+            ".{}.{}.{}.tmp",
+            path.file_name().and_then(|n| n.to_str()).unwrap_or("entry"),
+            std::process::id(),
+            TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::write(&tmp_path, data)
+            .map_err(KicadError::Io)?;
+        if let Err(e) = fs::rename(&tmp_path, &path) {
+            let _ = fs::remove_file(&tmp_path);
+            return Err(KicadError::Io(e).into());
+        }
+
+        self.evict_to_size()
+    }
+
+    /// List all cache entries as (path, size, modified)
+    fn entries(&self) -> Vec<(PathBuf, u64, SystemTime)> {
+        let mut entries = Vec::new();
+
+        for subdir in ["components", "models"] {
+            let Ok(read_dir) = fs::read_dir(self.dir.join(subdir)) else {
+                continue;
+            };
+
+            for entry in read_dir.flatten() {
+                let path = entry.path();
+                let is_tmp = path.file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| n.starts_with('.'));
+                if is_tmp {
+                    continue;
+                }
+
+                if let Ok(metadata) = entry.metadata() {
+                    if metadata.is_file() {
+                        let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                        entries.push((path, metadata.len(), modified));
+                    }
+                }
+            }
+        }
+
+        entries
+    }
+
+    fn evict_to_size(&self) -> Result<()> {
+        let mut entries = self.entries();
+        let mut total: u64 = entries.iter().map(|(_, size, _)| size).sum();
+        if total <= self.max_size {
+            return Ok(());
+        }
+
+        // Oldest first
+        entries.sort_by_key(|(_, _, modified)| *modified);
+
+        for (path, size, _) in entries {
+            if total <= self.max_size {
+                break;
+            }
+            log::debug!("Evicting cache entry: {}", path.display());
+            if fs::remove_file(&path).is_ok() {
+                total -= size;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Count entries and total size
+    pub fn stats(&self) -> CacheStats {
+        let mut stats = CacheStats::default();
+
+        for (path, size, _) in self.entries() {
+            stats.total_bytes += size;
+            if path.parent().and_then(|p| p.file_name()).is_some_and(|n| n == "components") {
+                stats.components += 1;
+            } else {
+                stats.models += 1;
+            }
+        }
+
+        stats
+    }
+
+    /// Remove every cached entry
+    pub fn clear(&self) -> Result<()> {
+        for subdir in ["components", "models"] {
+            let path = self.dir.join(subdir);
+            if path.exists() {
+                fs::remove_dir_all(&path)
+                    .map_err(KicadError::Io)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Component source that serves fresh entries from a `ResponseCache`
+///
+/// On a miss (or when `force_refresh` is set) the inner source is queried and
+/// the result stored. If the inner source fails, a stale entry is used so
+/// components can be reconverted offline.
+#[derive(Debug, Clone)]
+pub struct CachedSource<S: ComponentSource> {
+    inner: S,
+    cache: ResponseCache,
+    force_refresh: bool,
+}
+
+impl<S: ComponentSource> CachedSource<S> {
+    pub fn new(inner: S, cache: ResponseCache, force_refresh: bool) -> Self {
+        Self {
+            inner,
+            cache,
+            force_refresh,
+        }
+    }
+
+    async fn fetch_cached<F, Fut>(&self, kind: CacheKind, key: &str, fetch: F) -> Result<Vec<u8>>
+    where
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = Result<Vec<u8>>>,
+    {
+        if !self.force_refresh {
+            if let Some(data) = self.cache.get(kind, key) {
+                log::info!("Using cached {:?} for {}", kind, key);
+                return Ok(data);
+            }
+        }
+
+        match fetch().await {
+            Ok(data) => {
+                if let Err(e) = self.cache.put(kind, key, &data) {
+                    log::warn!("Failed to cache {:?} for {}: {}", kind, key, e);
+                }
+                Ok(data)
+            }
+            Err(e) => match self.cache.get_stale(kind, key) {
+                Some(data) => {
+                    log::warn!("Fetching {:?} for {} failed ({}), using stale cache entry", kind, key, e);
+                    Ok(data)
+                }
+                None => Err(e),
+            },
+        }
+    }
+}
+
+impl<S: ComponentSource> ComponentSource for CachedSource<S> {
+    async fn fetch_component_response(&self, lcsc_id: &str) -> Result<Vec<u8>> {
+        self.fetch_cached(CacheKind::Component, lcsc_id, || async {
+            let body = self.inner.fetch_component_response(lcsc_id).await?;
+            // Only cache responses that describe a valid component
+            EasyedaApi::parse_component_response(lcsc_id, &body)?;
+            Ok(body)
+        }).await
+    }
+
+    async fn download_3d_obj(&self, uuid: &str) -> Result<Vec<u8>> {
+        self.fetch_cached(CacheKind::ModelObj, uuid, || self.inner.download_3d_obj(uuid)).await
+    }
+
+    async fn download_3d_step(&self, uuid: &str) -> Result<Vec<u8>> {
+        self.fetch_cached(CacheKind::ModelStep, uuid, || self.inner.download_3d_step(uuid)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::source::FixtureSource;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("nlbn_cache_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_put_get_and_ttl() {
+        let dir = temp_dir("ttl");
+        let cache = ResponseCache::new(&dir);
+
+        cache.put(CacheKind::ModelObj, "abc", b"obj data").unwrap();
+        assert_eq!(cache.get(CacheKind::ModelObj, "abc").unwrap(), b"obj data");
+        assert!(cache.get(CacheKind::ModelStep, "abc").is_none());
+
+        let expired = cache.clone().with_ttl(Duration::ZERO);
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(expired.get(CacheKind::ModelObj, "abc").is_none());
+        assert_eq!(expired.get_stale(CacheKind::ModelObj, "abc").unwrap(), b"obj data");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_size_limit_evicts_oldest() {
+        let dir = temp_dir("evict");
+        let cache = ResponseCache::new(&dir).with_max_size(10);
+
+        cache.put(CacheKind::ModelObj, "old", b"123456").unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+        cache.put(CacheKind::ModelObj, "new", b"123456").unwrap();
+
+        assert!(cache.get(CacheKind::ModelObj, "old").is_none());
+        assert!(cache.get(CacheKind::ModelObj, "new").is_some());
+        assert_eq!(cache.stats(), CacheStats { components: 0, models: 1, total_bytes: 6 });
+
+        cache.clear().unwrap();
+        assert_eq!(cache.stats(), CacheStats::default());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_cached_source_falls_back_to_stale_entry() {
+        let dir = temp_dir("fallback");
+        let cache = ResponseCache::new(&dir.join("cache")).with_ttl(Duration::ZERO);
+        cache.put(CacheKind::ModelObj, "abc", b"cached obj").unwrap();
+
+        // The fixture directory is empty, so the inner source always fails
+        let source = CachedSource::new(FixtureSource::new(&dir), cache, false);
+        assert_eq!(source.download_3d_obj("abc").await.unwrap(), b"cached obj");
+        assert!(source.download_3d_step("abc").await.is_err());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,9 +1,21 @@
+use super::super::KicadVersion;
+use super::super::converter::Converter;
 use super::super::error::{EasyedaError, Result};
 use super::models::*;
 
 pub struct SymbolImporter;
 
 impl SymbolImporter {
+    /// Parse every unit of a part: one symbol per sub-part, or the main
+    /// shape list when the part has no sub-parts
+    pub fn parse_units(data_str: &[String], sub_parts: &[Vec<String>]) -> Result<Vec<EeSymbol>> {
+        if sub_parts.is_empty() {
+            return Ok(vec![Self::parse(data_str)?]);
+        }
+
+        sub_parts.iter().map(|shapes| Self::parse(shapes)).collect()
+    }
+
     pub fn parse(data_str: &[String]) -> Result<EeSymbol> {
         log::debug!("Parsing symbol with {} shapes", data_str.len());
 
@@ -121,9 +133,11 @@ impl SymbolImporter {
                     }
                 }
                 "T" => {
-                    // Text: T~x~y~rotation~text~id~locked~layerid~type~fontSize
-                    if let Ok(text) = Self::parse_text(&fields) {
-                        symbol.texts.push(text);
+                    // Text: T~mark~x~y~rotation~color~font_family~font_size~...~text~visible~...
+                    match Self::parse_text(&fields) {
+                        Ok(Some(text)) => symbol.texts.push(text),
+                        Ok(None) => {}
+                        Err(_) => log::warn!("Failed to parse text from: {}", shape),
                     }
                 }
                 "PATH" => {
@@ -132,11 +146,9 @@ impl SymbolImporter {
                         symbol.paths.push(path);
                     }
                 }
-                "LIB" => {
-                    // Library info: LIB~x~y~package~id~locked
-                    if fields.len() > 3 {
-                        symbol.name = fields[3].to_string();
-                    }
+                // Library info: LIB~x~y~package~id~locked
+                "LIB" if fields.len() > 3 => {
+                    symbol.name = fields[3].to_string();
                 }
                 _ => {}
             }
@@ -147,8 +159,9 @@ impl SymbolImporter {
             symbol.prefix = "U".to_string();
         }
 
-        log::info!("Parsed symbol: {} pins, {} rectangles, {} circles, {} ellipses, {} polylines",
-                   symbol.pins.len(), symbol.rectangles.len(), symbol.circles.len(), symbol.ellipses.len(), symbol.polylines.len());
+        log::info!("Parsed symbol: {} pins, {} rectangles, {} circles, {} ellipses, {} arcs, {} polylines, {} polygons, {} paths, {} texts",
+                   symbol.pins.len(), symbol.rectangles.len(), symbol.circles.len(), symbol.ellipses.len(), symbol.arcs.len(),
+                   symbol.polylines.len(), symbol.polygons.len(), symbol.paths.len(), symbol.texts.len());
 
         Ok(symbol)
     }
@@ -276,6 +289,7 @@ impl SymbolImporter {
             height,
             stroke_width: 1.0,
             fill,
+            layer_id: 0,
         })
     }
 
@@ -304,6 +318,7 @@ impl SymbolImporter {
             radius,
             stroke_width: 1.0,
             fill,
+            layer_id: 0,
         })
     }
 
@@ -361,72 +376,16 @@ impl SymbolImporter {
             start_angle,
             end_angle,
             stroke_width: 1.0,
+            layer_id: 0,
         })
     }
 
     fn parse_svg_arc(fields: &[&str]) -> Result<Vec<EeArc>> {
-        use super::svg_parser::{parse_svg_path, SvgCommand};
-
         if fields.len() < 2 {
             return Err(EasyedaError::InvalidData("Invalid SVG arc data".to_string()).into());
         }
 
-        let svg_path = fields[1];
-        let commands = parse_svg_path(svg_path)?;
-
-        let mut arcs = Vec::new();
-        let mut current_pos = (0.0, 0.0);
-
-        for cmd in commands {
-            match cmd {
-                SvgCommand::MoveTo { x, y } => {
-                    current_pos = (x, y);
-                }
-                SvgCommand::Arc { rx, ry, angle: _, large_arc: _, sweep, x, y } => {
-                    // Convert SVG arc to center-based arc
-                    // For simplicity, approximate with center at midpoint
-                    let cx = (current_pos.0 + x) / 2.0;
-                    let cy = (current_pos.1 + y) / 2.0;
-                    let radius = ((rx + ry) / 2.0).abs();
-
-                    // Calculate angles from start and end points
-                    let start_angle = ((current_pos.1 - cy).atan2(current_pos.0 - cx)).to_degrees();
-                    let end_angle = ((y - cy).atan2(x - cx)).to_degrees();
-
-                    // Adjust angles based on sweep direction
-                    let (start_angle, end_angle) = if sweep {
-                        if end_angle < start_angle {
-                            (start_angle, end_angle + 360.0)
-                        } else {
-                            (start_angle, end_angle)
-                        }
-                    } else {
-                        if start_angle < end_angle {
-                            (start_angle + 360.0, end_angle)
-                        } else {
-                            (start_angle, end_angle)
-                        }
-                    };
-
-                    arcs.push(EeArc {
-                        x: cx,
-                        y: cy,
-                        radius,
-                        start_angle,
-                        end_angle,
-                        stroke_width: 1.0,
-                    });
-
-                    current_pos = (x, y);
-                }
-                SvgCommand::LineTo { x, y } => {
-                    current_pos = (x, y);
-                }
-                SvgCommand::ClosePath => {}
-            }
-        }
-
-        Ok(arcs)
+        svg_path_to_arcs(fields[1], 1.0, 0)
     }
 
     fn parse_polyline(fields: &[&str]) -> Result<EePolyline> {
@@ -526,31 +485,35 @@ impl SymbolImporter {
         })
     }
 
-    fn parse_text(fields: &[&str]) -> Result<EeText> {
-        if fields.len() < 5 {
+    fn parse_text(fields: &[&str]) -> Result<Option<EeText>> {
+        if fields.len() < 13 {
             return Err(EasyedaError::InvalidData("Invalid text data".to_string()).into());
         }
 
-        let x = fields[1].parse::<f64>()
+        // T~mark~x~y~rotation~color~font_family~font_size~font_weight~font_style~baseline~type~text~visible~anchor~id~locked
+        let x = fields[2].parse::<f64>()
             .map_err(|_| EasyedaError::InvalidData("Invalid text X".to_string()))?;
-        let y = fields[2].parse::<f64>()
+        let y = fields[3].parse::<f64>()
             .map_err(|_| EasyedaError::InvalidData("Invalid text Y".to_string()))?;
-        let rotation = fields[3].parse::<i32>().unwrap_or(0);
-        let text = fields[4].to_string();
+        let rotation = fields[4].parse::<i32>().unwrap_or(0);
+        let text = fields[12].to_string();
 
-        let font_size = if fields.len() > 8 {
-            fields[8].parse::<f64>().unwrap_or(12.0)
-        } else {
-            12.0
-        };
+        // Hidden texts and empty labels are not drawn by EasyEDA either
+        let visible = fields.get(13).is_none_or(|v| *v != "0");
+        if !visible || text.trim().is_empty() {
+            return Ok(None);
+        }
 
-        Ok(EeText {
+        // Font size is given in points, e.g. "7pt" (empty means the 7pt default)
+        let font_size = fields[7].trim_end_matches("pt").parse::<f64>().unwrap_or(7.0);
+
+        Ok(Some(EeText {
             text,
             x,
             y,
             rotation,
             font_size,
-        })
+        }))
     }
 
     fn parse_path(fields: &[&str]) -> Result<EePath> {
@@ -604,6 +567,7 @@ impl FootprintImporter {
             texts: Vec::new(),
             holes: Vec::new(),
             vias: Vec::new(),
+            solid_regions: Vec::new(),
             svg_nodes: Vec::new(),
         };
 
@@ -632,8 +596,9 @@ impl FootprintImporter {
                     }
                 }
                 "ARC" => {
-                    if let Ok(arc) = Self::parse_arc(&fields) {
-                        footprint.arcs.push(arc);
+                    match Self::parse_arcs(&fields) {
+                        Ok(arcs) => footprint.arcs.extend(arcs),
+                        Err(_) => log::warn!("Failed to parse footprint arc from: {}", shape),
                     }
                 }
                 "RECT" => {
@@ -656,6 +621,11 @@ impl FootprintImporter {
                         footprint.vias.push(via);
                     }
                 }
+                "SOLIDREGION" => {
+                    if let Ok(region) = Self::parse_solid_region(&fields) {
+                        footprint.solid_regions.push(region);
+                    }
+                }
                 "SVGNODE" => {
                     if let Ok(svg_node) = Self::parse_svg_node(&fields) {
                         footprint.svg_nodes.push(svg_node);
@@ -725,6 +695,9 @@ impl FootprintImporter {
             None
         };
 
+        // Field 15 is the plated flag ("Y"/"N") - field 14 is the hole outline
+        let plated = fields.get(15).is_none_or(|v| *v != "N");
+
         Ok(EePad {
             number,
             shape,
@@ -737,6 +710,7 @@ impl FootprintImporter {
             hole_length,
             points,
             layer_id,
+            plated,
         })
     }
 
@@ -773,39 +747,32 @@ impl FootprintImporter {
         let radius = fields[3].parse::<f64>()
             .map_err(|_| EasyedaError::InvalidData("Invalid circle radius".to_string()))?;
 
+        // CIRCLE~cx~cy~radius~stroke_width~layer_id~id~locked
+        let stroke_width = fields[4].parse::<f64>().unwrap_or(1.0);
+        let layer_id = fields.get(5).and_then(|v| v.parse::<i32>().ok()).unwrap_or(3);
+
         Ok(EeCircle {
             cx,
             cy,
             radius,
-            stroke_width: 1.0,
+            stroke_width,
             fill: false,
+            layer_id,
         })
     }
 
-    fn parse_arc(fields: &[&str]) -> Result<EeArc> {
-        if fields.len() < 7 {
+    fn parse_arcs(fields: &[&str]) -> Result<Vec<EeArc>> {
+        if fields.len() < 5 {
             return Err(EasyedaError::InvalidData("Invalid arc data".to_string()).into());
         }
 
-        let x = fields[2].parse::<f64>()
-            .map_err(|_| EasyedaError::InvalidData("Invalid arc X".to_string()))?;
-        let y = fields[3].parse::<f64>()
-            .map_err(|_| EasyedaError::InvalidData("Invalid arc Y".to_string()))?;
-        let radius = fields[4].parse::<f64>()
-            .map_err(|_| EasyedaError::InvalidData("Invalid arc radius".to_string()))?;
-        let start_angle = fields[5].parse::<f64>()
-            .map_err(|_| EasyedaError::InvalidData("Invalid arc start angle".to_string()))?;
-        let end_angle = fields[6].parse::<f64>()
-            .map_err(|_| EasyedaError::InvalidData("Invalid arc end angle".to_string()))?;
+        // ARC~stroke_width~layer_id~net~path~helper_dots~id~locked
+        let stroke_width = fields[1].parse::<f64>()
+            .map_err(|_| EasyedaError::InvalidData("Invalid arc width".to_string()))?;
+        let layer_id = fields[2].parse::<i32>()
+            .map_err(|_| EasyedaError::InvalidData("Invalid arc layer_id".to_string()))?;
 
-        Ok(EeArc {
-            x,
-            y,
-            radius,
-            start_angle,
-            end_angle,
-            stroke_width: 1.0,
-        })
+        svg_path_to_arcs(fields[4], stroke_width, layer_id)
     }
 
     fn parse_rectangle(fields: &[&str]) -> Result<EeRectangle> {
@@ -813,22 +780,29 @@ impl FootprintImporter {
             return Err(EasyedaError::InvalidData("Invalid rectangle data".to_string()).into());
         }
 
-        let x = fields[2].parse::<f64>()
+        // RECT~x~y~width~height~layer_id~id~locked~stroke_width~fill
+        let x = fields[1].parse::<f64>()
             .map_err(|_| EasyedaError::InvalidData("Invalid rectangle X".to_string()))?;
-        let y = fields[3].parse::<f64>()
+        let y = fields[2].parse::<f64>()
             .map_err(|_| EasyedaError::InvalidData("Invalid rectangle Y".to_string()))?;
-        let width = fields[4].parse::<f64>()
+        let width = fields[3].parse::<f64>()
             .map_err(|_| EasyedaError::InvalidData("Invalid rectangle width".to_string()))?;
-        let height = fields[5].parse::<f64>()
+        let height = fields[4].parse::<f64>()
             .map_err(|_| EasyedaError::InvalidData("Invalid rectangle height".to_string()))?;
+        let layer_id = fields[5].parse::<i32>()
+            .map_err(|_| EasyedaError::InvalidData("Invalid rectangle layer_id".to_string()))?;
+
+        let stroke_width = fields.get(8).and_then(|v| v.parse::<f64>().ok()).unwrap_or(1.0);
+        let fill = fields.get(9).is_some_and(|v| !v.is_empty() && *v != "none" && *v != "0");
 
         Ok(EeRectangle {
             x,
             y,
             width,
             height,
-            stroke_width: 1.0,
-            fill: false,
+            stroke_width,
+            fill,
+            layer_id,
         })
     }
 
@@ -896,6 +870,22 @@ impl FootprintImporter {
         })
     }
 
+    fn parse_solid_region(fields: &[&str]) -> Result<EeSolidRegion> {
+        if fields.len() < 5 {
+            return Err(EasyedaError::InvalidData("Invalid solid region data".to_string()).into());
+        }
+
+        // SOLIDREGION~layer_id~net~path~type~id~~~~locked
+        let layer_id = fields[1].parse::<i32>()
+            .map_err(|_| EasyedaError::InvalidData("Invalid solid region layer_id".to_string()))?;
+
+        Ok(EeSolidRegion {
+            layer_id,
+            path: fields[3].to_string(),
+            region_type: fields[4].to_string(),
+        })
+    }
+
     fn parse_svg_node(fields: &[&str]) -> Result<EeSvgNode> {
         if fields.len() < 3 {
             return Err(EasyedaError::InvalidData("Invalid SVG node data".to_string()).into());
@@ -911,3 +901,68 @@ impl FootprintImporter {
         })
     }
 }
+
+/// Convert the arc commands of an SVG path into center-based arcs
+fn svg_path_to_arcs(svg_path: &str, stroke_width: f64, layer_id: i32) -> Result<Vec<EeArc>> {
+    use super::svg_parser::{parse_svg_path, SvgCommand};
+
+    let commands = parse_svg_path(svg_path)?;
+    let converter = Converter::new(KicadVersion::V6);
+
+    let mut arcs = Vec::new();
+    let mut current_pos = (0.0, 0.0);
+
+    for cmd in commands {
+        match cmd {
+            SvgCommand::MoveTo { x, y } => {
+                current_pos = (x, y);
+            }
+            SvgCommand::Arc { rx, ry, angle, large_arc, sweep, x, y } => {
+                // Convert SVG endpoint arc to center-based arc
+                let (cx, cy, start_angle, end_angle) = converter.compute_arc_center(
+                    current_pos,
+                    (x, y),
+                    (rx, ry),
+                    angle,
+                    large_arc,
+                    sweep,
+                )?;
+                // Radii may have been scaled up to reach the end point
+                let radius = (current_pos.0 - cx).hypot(current_pos.1 - cy);
+
+                // Adjust angles based on sweep direction
+                let (start_angle, end_angle) = if sweep {
+                    if end_angle < start_angle {
+                        (start_angle, end_angle + 360.0)
+                    } else {
+                        (start_angle, end_angle)
+                    }
+                } else {
+                    if start_angle < end_angle {
+                        (start_angle + 360.0, end_angle)
+                    } else {
+                        (start_angle, end_angle)
+                    }
+                };
+
+                arcs.push(EeArc {
+                    x: cx,
+                    y: cy,
+                    radius,
+                    start_angle,
+                    end_angle,
+                    stroke_width,
+                    layer_id,
+                });
+
+                current_pos = (x, y);
+            }
+            SvgCommand::LineTo { x, y } => {
+                current_pos = (x, y);
+            }
+            SvgCommand::ClosePath => {}
+        }
+    }
+
+    Ok(arcs)
+}
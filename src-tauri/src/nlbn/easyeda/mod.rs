@@ -1,9 +1,13 @@
 pub mod api;
+pub mod cache;
 pub mod importer;
 pub mod models;
+pub mod source;
 pub mod svg_parser;
 
 pub use api::EasyedaApi;
+pub use cache::{CachedSource, CacheKind, CacheStats, ResponseCache};
 pub use importer::{SymbolImporter, FootprintImporter};
 pub use models::*;
+pub use source::{ComponentSource, FixtureSource};
 pub use svg_parser::parse_svg_path;
@@ -4,10 +4,19 @@ use serde::{Deserialize, Serialize};
 pub struct ComponentData {
     pub lcsc_id: String,
     pub title: String,
+    /// Reference designator prefix, e.g. `D` for EasyEDA's `D?`; empty if unknown
+    #[serde(default)]
+    pub prefix: String,
     pub data_str: Vec<String>,
+    /// Shape arrays of each sub-part (unit) of multi-unit parts; empty for single-unit parts
+    #[serde(default)]
+    pub sub_parts: Vec<Vec<String>>,
     pub bbox_x: f64,  // Symbol bbox
     pub bbox_y: f64,  // Symbol bbox
     pub package_detail: Vec<String>,
+    /// Package (footprint) title in EasyEDA, e.g. `SOIC-8_L4.9-W3.9-P1.27-LS6.0-BL`
+    #[serde(default)]
+    pub package_name: String,
     pub package_bbox_x: f64,  // Footprint bbox
     pub package_bbox_y: f64,  // Footprint bbox
     pub model_3d: Option<Model3dInfo>,
@@ -20,6 +29,15 @@ pub struct ComponentData {
 pub struct Model3dInfo {
     pub uuid: String,
     pub title: String,
+    /// Model position on the EasyEDA canvas (`c_origin`); the package origin if absent
+    #[serde(default)]
+    pub origin: Option<(f64, f64)>,
+    /// Height of the model above the board (`z`), in EasyEDA units
+    #[serde(default)]
+    pub z: f64,
+    /// Rotation around the X, Y and Z axes in degrees (`c_rotation`)
+    #[serde(default)]
+    pub rotation: (f64, f64, f64),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -36,6 +54,8 @@ pub struct ApiResult {
     #[serde(rename = "packageDetail")]
     pub package_detail: Option<serde_json::Value>,
     pub lcsc: Option<serde_json::Value>,
+    /// Sub-part symbols of multi-unit parts, each with its own `dataStr`
+    pub subparts: Option<Vec<serde_json::Value>>,
 }
 
 // EasyEDA Symbol structures
@@ -77,6 +97,7 @@ pub struct EeRectangle {
     pub height: f64,
     pub stroke_width: f64,
     pub fill: bool,
+    pub layer_id: i32,  // Footprints only; 0 for symbols
 }
 
 #[derive(Debug, Clone)]
@@ -86,6 +107,7 @@ pub struct EeCircle {
     pub radius: f64,
     pub stroke_width: f64,
     pub fill: bool,
+    pub layer_id: i32,  // Footprints only; 0 for symbols
 }
 
 #[derive(Debug, Clone)]
@@ -106,6 +128,7 @@ pub struct EeArc {
     pub start_angle: f64,
     pub end_angle: f64,
     pub stroke_width: f64,
+    pub layer_id: i32,  // Footprints only; 0 for symbols
 }
 
 #[derive(Debug, Clone)]
@@ -149,6 +172,7 @@ pub struct EeFootprint {
     pub texts: Vec<EeText>,
     pub holes: Vec<EeHole>,
     pub vias: Vec<EeVia>,
+    pub solid_regions: Vec<EeSolidRegion>,
     pub svg_nodes: Vec<EeSvgNode>,
 }
 
@@ -165,6 +189,7 @@ pub struct EePad {
     pub hole_length: Option<f64>,  // For elliptical drills
     pub points: String,  // For polygon pads
     pub layer_id: i32,
+    pub plated: bool,  // Unplated holes have no copper
 }
 
 #[derive(Debug, Clone)]
@@ -191,6 +216,13 @@ pub struct EeVia {
     pub radius: f64,    // Hole radius (drill = radius * 2)
 }
 
+#[derive(Debug, Clone)]
+pub struct EeSolidRegion {
+    pub layer_id: i32,
+    pub path: String,  // SVG path outline
+    pub region_type: String,  // "solid", "cutout" (keepout) or "npth" (board cutout)
+}
+
 #[derive(Debug, Clone)]
 pub struct EeSvgNode {
     pub path: String,
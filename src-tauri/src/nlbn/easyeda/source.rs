@@ -0,0 +1,183 @@
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use super::super::error::{EasyedaError, KicadError, Result};
+use super::api::EasyedaApi;
+use super::models::ComponentData;
+
+/// Provider of raw component data and 3D model blobs
+///
+/// `ComponentConverter` is generic over this trait so the conversion pipeline
+/// can run against the live EasyEDA API or against recorded fixtures.
+pub trait ComponentSource: Sync {
+    /// Fetch the raw `/api/products/{id}/components` response body for an LCSC ID
+    fn fetch_component_response(&self, lcsc_id: &str) -> impl Future<Output = Result<Vec<u8>>> + Send;
+
+    /// Fetch and parse the component data for an LCSC ID
+    fn get_component_data(&self, lcsc_id: &str) -> impl Future<Output = Result<ComponentData>> + Send {
+        async move {
+            let body = self.fetch_component_response(lcsc_id).await?;
+            EasyedaApi::parse_component_response(lcsc_id, &body)
+        }
+    }
+
+    /// Download the OBJ 3D model with the given UUID
+    fn download_3d_obj(&self, uuid: &str) -> impl Future<Output = Result<Vec<u8>>> + Send;
+
+    /// Download the STEP 3D model with the given UUID
+    fn download_3d_step(&self, uuid: &str) -> impl Future<Output = Result<Vec<u8>>> + Send;
+}
+
+impl ComponentSource for EasyedaApi {
+    fn fetch_component_response(&self, lcsc_id: &str) -> impl Future<Output = Result<Vec<u8>>> + Send {
+        EasyedaApi::fetch_component_response(self, lcsc_id)
+    }
+
+    fn download_3d_obj(&self, uuid: &str) -> impl Future<Output = Result<Vec<u8>>> + Send {
+        EasyedaApi::download_3d_obj(self, uuid)
+    }
+
+    fn download_3d_step(&self, uuid: &str) -> impl Future<Output = Result<Vec<u8>>> + Send {
+        EasyedaApi::download_3d_step(self, uuid)
+    }
+}
+
+/// Offline source reading recorded responses from a directory
+///
+/// Layout:
+/// - `<LCSC ID>.json` - raw `/api/products/{id}/components` response body
+/// - `<uuid>.obj` - OBJ model as served by `modules.easyeda.com/3dmodel/{uuid}`
+/// - `<uuid>.step` - STEP model (optional)
+#[derive(Debug, Clone)]
+pub struct FixtureSource {
+    dir: PathBuf,
+}
+
+impl FixtureSource {
+    pub fn new(dir: &Path) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+        }
+    }
+
+    fn read_fixture(&self, file_name: &str) -> Option<std::io::Result<Vec<u8>>> {
+        let path = self.dir.join(file_name);
+        if !path.exists() {
+            return None;
+        }
+
+        log::info!("Reading fixture: {}", path.display());
+        Some(std::fs::read(path))
+    }
+}
+
+impl ComponentSource for FixtureSource {
+    async fn fetch_component_response(&self, lcsc_id: &str) -> Result<Vec<u8>> {
+        let body = self.read_fixture(&format!("{}.json", lcsc_id))
+            .ok_or_else(|| EasyedaError::ComponentNotFound(lcsc_id.to_string()))?
+            .map_err(KicadError::Io)?;
+
+        Ok(body)
+    }
+
+    async fn download_3d_obj(&self, uuid: &str) -> Result<Vec<u8>> {
+        let data = self.read_fixture(&format!("{}.obj", uuid))
+            .ok_or_else(|| EasyedaError::InvalidData(format!("Failed to download OBJ: {}", uuid)))?
+            .map_err(KicadError::Io)?;
+
+        Ok(data)
+    }
+
+    async fn download_3d_step(&self, uuid: &str) -> Result<Vec<u8>> {
+        let data = self.read_fixture(&format!("{}.step", uuid))
+            .ok_or_else(|| EasyedaError::InvalidData(format!("Failed to download STEP: {}", uuid)))?
+            .map_err(KicadError::Io)?;
+
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("nlbn_fixture_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[tokio::test]
+    async fn test_fixture_component_data() {
+        let dir = fixture_dir("component");
+        let response = r#"{
+            "success": true,
+            "result": {
+                "title": "NE555",
+                "dataStr": {
+                    "head": {"x": 400, "y": 300, "c_para": {"BOM_Manufacturer": "TI"}},
+                    "shape": ["R~-20~-30~2~2~40~60~#880000~1~0~none~gge1~0~"]
+                },
+                "packageDetail": {
+                    "dataStr": {
+                        "head": {"x": 4000, "y": 3000},
+                        "shape": [
+                            "PAD~RECT~4000~3000~6~2~1~~1~0~3994 2999 4006 2999 4006 3001 3994 3001~0~gge2~0~~Y~0~0~0.2~4000,3000",
+                            "SVGNODE~{\"attrs\":{\"c_etype\":\"outline3D\",\"uuid\":\"abc123\",\"title\":\"SOIC-8\"}}"
+                        ]
+                    }
+                },
+                "lcsc": {"url": "https://example.com/ne555.pdf"}
+            }
+        }"#;
+        std::fs::write(dir.join("C1234.json"), response).unwrap();
+        std::fs::write(dir.join("abc123.obj"), b"v 0 0 0\n").unwrap();
+
+        let source = FixtureSource::new(&dir);
+        let data = source.get_component_data("C1234").await.unwrap();
+        assert_eq!(data.title, "NE555");
+        assert_eq!(data.manufacturer, "TI");
+        assert_eq!(data.data_str.len(), 1);
+        assert_eq!(data.package_detail.len(), 2);
+        assert_eq!(data.package_bbox_x, 4000.0);
+        assert_eq!(data.model_3d.as_ref().map(|m| m.uuid.as_str()), Some("abc123"));
+
+        assert_eq!(source.download_3d_obj("abc123").await.unwrap(), b"v 0 0 0\n");
+        assert!(source.download_3d_step("abc123").await.is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_fixture_multi_unit_component() {
+        let dir = fixture_dir("subparts");
+        let response = r#"{
+            "success": true,
+            "result": {
+                "title": "LM358",
+                "subparts": [
+                    {"dataStr": {"head": {"x": 400, "y": 300, "c_para": {"BOM_Manufacturer": "TI"}}, "shape": ["PL~400 300 410 300~#880000~1~0~none~gge1~0"]}},
+                    {"dataStr": {"head": {"x": 400, "y": 300}, "shape": ["PL~400 300 410 300~#880000~1~0~none~gge2~0", "PL~400 310 410 310~#880000~1~0~none~gge3~0"]}}
+                ]
+            }
+        }"#;
+        std::fs::write(dir.join("C7950.json"), response).unwrap();
+
+        let data = FixtureSource::new(&dir).get_component_data("C7950").await.unwrap();
+        assert_eq!(data.sub_parts.len(), 2);
+        assert_eq!(data.sub_parts[1].len(), 2);
+        // Metadata falls back to the first sub-part when there is no top-level dataStr
+        assert_eq!(data.manufacturer, "TI");
+        assert_eq!(data.bbox_x, 400.0);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_fixture_missing_component() {
+        let dir = fixture_dir("missing");
+        let source = FixtureSource::new(&dir);
+        assert!(source.get_component_data("C999").await.is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -29,9 +29,18 @@ pub enum KicadError {
     #[error("Failed to export 3D model: {0}")]
     ModelExport(String),
 
+    #[error("Failed to parse KiCad file: {0}")]
+    Parse(String),
+
     #[error("Invalid KiCad version")]
     InvalidVersion,
 
+    #[error("Invalid library name: {0:?}")]
+    InvalidLibraryName(String),
+
+    #[error("{0} is locked by another process (gave up after {1:.1} s); close other nlbn instances writing to this library and retry")]
+    Locked(String, f64),
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 }
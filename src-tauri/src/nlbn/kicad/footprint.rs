@@ -5,11 +5,148 @@ pub struct KiFootprint {
     pub tracks: Vec<KiTrack>,
     pub circles: Vec<KiCircle>,
     pub arcs: Vec<KiArc>,
+    pub rectangles: Vec<KiRectangle>,
+    pub polygons: Vec<KiPolygon>,
+    pub zones: Vec<KiZone>,
     pub texts: Vec<KiText>,
     pub lines: Vec<KiLine>,
     pub model_3d: Option<Ki3dModel>,
 }
 
+/// Axis-aligned bounds as `(min_x, min_y, max_x, max_y)`
+pub type Bounds = (f64, f64, f64, f64);
+
+impl KiFootprint {
+    /// Move all pads and graphics by `(dx, dy)`; the reference and value
+    /// texts are already placed relative to the origin and stay put
+    pub fn translate(&mut self, dx: f64, dy: f64) {
+        let shift = |points: &mut Vec<(f64, f64)>| {
+            for (x, y) in points.iter_mut() {
+                *x += dx;
+                *y += dy;
+            }
+        };
+
+        for pad in &mut self.pads {
+            pad.pos_x += dx;
+            pad.pos_y += dy;
+        }
+        for track in &mut self.tracks {
+            track.start_x += dx;
+            track.start_y += dy;
+            track.end_x += dx;
+            track.end_y += dy;
+        }
+        for line in &mut self.lines {
+            line.start_x += dx;
+            line.start_y += dy;
+            line.end_x += dx;
+            line.end_y += dy;
+        }
+        for circle in &mut self.circles {
+            circle.center_x += dx;
+            circle.center_y += dy;
+            circle.end_x += dx;
+            circle.end_y += dy;
+        }
+        for arc in &mut self.arcs {
+            arc.start_x += dx;
+            arc.start_y += dy;
+            arc.mid_x += dx;
+            arc.mid_y += dy;
+            arc.end_x += dx;
+            arc.end_y += dy;
+        }
+        for rect in &mut self.rectangles {
+            rect.start_x += dx;
+            rect.start_y += dy;
+            rect.end_x += dx;
+            rect.end_y += dy;
+        }
+        for polygon in &mut self.polygons {
+            shift(&mut polygon.points);
+        }
+        for zone in &mut self.zones {
+            shift(&mut zone.points);
+        }
+    }
+
+    /// Surface-mount footprint: no plated through-hole pads (mounting holes
+    /// and locating pegs do not count)
+    pub fn is_smd(&self) -> bool {
+        !self.pads.iter().any(|pad| pad.pad_type == PadType::ThroughHole)
+    }
+
+    /// Bounds of the pad copper, or `None` without pads
+    pub fn pad_bounds(&self) -> Option<Bounds> {
+        let mut points = Vec::new();
+        for pad in &self.pads {
+            match &pad.polygon {
+                Some(polygon) => {
+                    points.extend(polygon.iter().map(|(x, y)| (pad.pos_x + x, pad.pos_y + y)));
+                }
+                None => {
+                    // Quarter turns swap the pad axes; other angles use the
+                    // circumscribed square
+                    let (half_x, half_y) = match pad.rotation.rem_euclid(180.0) {
+                        0.0 => (pad.size_x / 2.0, pad.size_y / 2.0),
+                        90.0 => (pad.size_y / 2.0, pad.size_x / 2.0),
+                        _ => {
+                            let half = pad.size_x.hypot(pad.size_y) / 2.0;
+                            (half, half)
+                        }
+                    };
+                    points.push((pad.pos_x - half_x, pad.pos_y - half_y));
+                    points.push((pad.pos_x + half_x, pad.pos_y + half_y));
+                }
+            }
+        }
+        bounds_of(points)
+    }
+
+    /// Bounds of all pads and graphics, or `None` for an empty footprint
+    pub fn bounds(&self) -> Option<Bounds> {
+        let mut points = Vec::new();
+        if let Some((min_x, min_y, max_x, max_y)) = self.pad_bounds() {
+            points.push((min_x, min_y));
+            points.push((max_x, max_y));
+        }
+        for line in &self.lines {
+            points.push((line.start_x, line.start_y));
+            points.push((line.end_x, line.end_y));
+        }
+        for circle in &self.circles {
+            let radius = (circle.end_x - circle.center_x).hypot(circle.end_y - circle.center_y);
+            points.push((circle.center_x - radius, circle.center_y - radius));
+            points.push((circle.center_x + radius, circle.center_y + radius));
+        }
+        for arc in &self.arcs {
+            points.extend([(arc.start_x, arc.start_y), (arc.mid_x, arc.mid_y), (arc.end_x, arc.end_y)]);
+        }
+        for rect in &self.rectangles {
+            points.push((rect.start_x, rect.start_y));
+            points.push((rect.end_x, rect.end_y));
+        }
+        for polygon in &self.polygons {
+            points.extend(&polygon.points);
+        }
+        for zone in &self.zones {
+            points.extend(&zone.points);
+        }
+        bounds_of(points)
+    }
+}
+
+fn bounds_of(points: Vec<(f64, f64)>) -> Option<Bounds> {
+    if points.is_empty() {
+        return None;
+    }
+    Some(points.iter().fold(
+        (f64::INFINITY, f64::INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY),
+        |(min_x, min_y, max_x, max_y), &(x, y)| (min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y)),
+    ))
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PadType {
     Smd,
@@ -74,13 +211,13 @@ pub struct KiPad {
     pub rotation: f64,
     pub layers: Vec<String>,
     pub drill: Option<Drill>,
-    pub polygon: Option<String>,  // For custom polygon pads
+    pub polygon: Option<Vec<(f64, f64)>>,  // Custom pad outline, relative to the pad anchor
 }
 
 #[derive(Debug, Clone)]
 pub struct Drill {
-    pub diameter: f64,
-    pub width: Option<f64>,  // For oval drills: width (if different from diameter)
+    pub diameter: f64,  // For oval drills: the X size
+    pub width: Option<f64>,  // For oval drills: the Y size
     pub offset_x: f64,
     pub offset_y: f64,
 }
@@ -118,6 +255,32 @@ pub struct KiArc {
     pub layer: String,
 }
 
+#[derive(Debug, Clone)]
+pub struct KiRectangle {
+    pub start_x: f64,
+    pub start_y: f64,
+    pub end_x: f64,
+    pub end_y: f64,
+    pub width: f64,
+    pub layer: String,
+    pub fill: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct KiPolygon {
+    pub points: Vec<(f64, f64)>,
+    pub width: f64,
+    pub layer: String,
+    pub fill: bool,
+}
+
+/// Copper keepout area
+#[derive(Debug, Clone)]
+pub struct KiZone {
+    pub points: Vec<(f64, f64)>,
+    pub layers: Vec<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct KiLine {
     pub start_x: f64,
@@ -146,3 +309,77 @@ pub struct Ki3dModel {
     pub scale: (f64, f64, f64),
     pub rotate: (f64, f64, f64),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pad(x: f64, y: f64, size_x: f64, size_y: f64, rotation: f64) -> KiPad {
+        KiPad {
+            number: "1".to_string(),
+            pad_type: PadType::Smd,
+            shape: PadShape::Rect,
+            pos_x: x,
+            pos_y: y,
+            size_x,
+            size_y,
+            rotation,
+            layers: vec!["F.Cu".to_string()],
+            drill: None,
+            polygon: None,
+        }
+    }
+
+    fn footprint(pads: Vec<KiPad>) -> KiFootprint {
+        KiFootprint {
+            name: "TEST".to_string(),
+            pads,
+            tracks: Vec::new(),
+            circles: Vec::new(),
+            arcs: Vec::new(),
+            rectangles: Vec::new(),
+            polygons: Vec::new(),
+            zones: Vec::new(),
+            texts: Vec::new(),
+            lines: Vec::new(),
+            model_3d: None,
+        }
+    }
+
+    #[test]
+    fn test_pad_bounds_rotated() {
+        let fp = footprint(vec![pad(0.0, 0.0, 4.0, 2.0, 0.0), pad(10.0, 0.0, 4.0, 2.0, 90.0)]);
+        assert_eq!(fp.pad_bounds(), Some((-2.0, -2.0, 11.0, 2.0)));
+        assert_eq!(footprint(Vec::new()).pad_bounds(), None);
+    }
+
+    #[test]
+    fn test_is_smd_ignores_mounting_holes() {
+        let mut peg = pad(0.0, 5.0, 3.0, 3.0, 0.0);
+        peg.pad_type = PadType::NpThroughHole;
+        peg.drill = Some(Drill { diameter: 3.0, width: None, offset_x: 0.0, offset_y: 0.0 });
+        let mut fp = footprint(vec![pad(0.0, 0.0, 4.0, 2.0, 0.0), peg]);
+        assert!(fp.is_smd());
+
+        fp.pads[0].pad_type = PadType::ThroughHole;
+        assert!(!fp.is_smd());
+    }
+
+    #[test]
+    fn test_translate_and_bounds() {
+        let mut fp = footprint(vec![pad(100.0, -50.0, 2.0, 2.0, 0.0)]);
+        fp.lines.push(KiLine {
+            start_x: 96.0,
+            start_y: -50.0,
+            end_x: 104.0,
+            end_y: -50.0,
+            width: 0.5,
+            layer: "F.SilkS".to_string(),
+        });
+        fp.translate(-100.0, 50.0);
+
+        assert_eq!(fp.pads[0].pos_x, 0.0);
+        assert_eq!(fp.pads[0].pos_y, 0.0);
+        assert_eq!(fp.bounds(), Some((-4.0, -1.0, 4.0, 1.0)));
+    }
+}
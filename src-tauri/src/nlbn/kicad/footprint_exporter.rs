@@ -1,34 +1,71 @@
 use super::super::converter::Converter;
 use super::super::error::Result;
 use super::footprint::*;
+use super::sexpr::{Num, SExpr};
 use super::super::KicadVersion;
+use std::cell::Cell;
 
 pub struct FootprintExporter {
+    version: KicadVersion,
     converter: Converter,
+    /// Hash of the footprint name, so item UUIDs are stable between runs
+    uuid_seed: Cell<u64>,
+    uuid_count: Cell<u64>,
 }
 
 impl FootprintExporter {
-    pub fn new() -> Self {
+    pub fn new(version: KicadVersion) -> Self {
         Self {
-            converter: Converter::new(KicadVersion::V6),
+            version,
+            converter: Converter::new(version),
+            uuid_seed: Cell::new(0),
+            uuid_count: Cell::new(0),
         }
     }
 
     pub fn export(&self, footprint: &KiFootprint) -> Result<String> {
-        let mut output = String::new();
-
-        // Module header
-        output.push_str(&format!("(footprint \"{}\" (version 20221018) (generator nlbn)\n", footprint.name));
-        output.push_str("  (layer \"F.Cu\")\n");
+        if self.version.is_legacy() {
+            return self.export_v5(footprint);
+        }
 
-        // Reference and value text
-        output.push_str("  (fp_text reference \"REF**\" (at 0 0) (layer \"F.SilkS\")\n");
-        output.push_str("    (effects (font (size 1 1) (thickness 0.15)))\n");
-        output.push_str("  )\n");
+        let mut output = String::new();
 
-        output.push_str(&format!("  (fp_text value \"{}\" (at 0 2.5) (layer \"F.Fab\")\n", footprint.name));
-        output.push_str("    (effects (font (size 1 1) (thickness 0.15)))\n");
-        output.push_str("  )\n");
+        self.uuid_seed.set(fnv1a(FNV_OFFSET, footprint.name.as_bytes()));
+        self.uuid_count.set(0);
+
+        if self.version >= KicadVersion::V8 {
+            // Header and fields as KiCad 8 writes them
+            output.push_str(&format!("(footprint \"{}\"\n", footprint.name));
+            output.push_str(&format!("  (version {})\n", self.version.footprint_version()));
+            output.push_str("  (generator \"nlbn\")\n");
+            output.push_str(&format!("  (generator_version \"{}\")\n", env!("CARGO_PKG_VERSION")));
+            output.push_str("  (layer \"F.Cu\")\n");
+
+            output.push_str(&self.format_property("Reference", "REF**", 0.0, "F.SilkS", false));
+            output.push_str(&self.format_property("Value", &footprint.name, 2.5, "F.Fab", false));
+            output.push_str(&self.format_property("Footprint", "", 0.0, "F.Fab", true));
+            output.push_str(&self.format_property("Datasheet", "", 0.0, "F.Fab", true));
+            output.push_str(&self.format_property("Description", "", 0.0, "F.Fab", true));
+            output.push_str(&self.format_attr(footprint));
+        } else {
+            // Module header
+            output.push_str(&format!(
+                "(footprint \"{}\" (version {}) (generator nlbn)\n",
+                footprint.name,
+                self.version.footprint_version()
+            ));
+            output.push_str("  (layer \"F.Cu\")\n");
+            output.push_str(&self.format_attr(footprint));
+
+            // Reference and value text
+            output.push_str("  (fp_text reference \"REF**\" (at 0 0) (layer \"F.SilkS\")\n");
+            output.push_str("    (effects (font (size 1 1) (thickness 0.15)))\n");
+            output.push_str("  )\n");
+
+            output.push_str(&format!("  (fp_text value \"{}\" (at 0 2.5) (layer \"F.Fab\")\n", footprint.name));
+            output.push_str("    (effects (font (size 1 1) (thickness 0.15)))\n");
+            output.push_str("  )\n");
+        }
 
         // Pads
         for pad in &footprint.pads {
@@ -50,11 +87,101 @@ impl FootprintExporter {
             output.push_str(&self.format_arc(arc));
         }
 
+        // Rectangles
+        for rect in &footprint.rectangles {
+            output.push_str(&self.format_rectangle(rect));
+        }
+
+        // Polygons
+        for polygon in &footprint.polygons {
+            output.push_str(&self.format_polygon(polygon));
+        }
+
+        // Keepout zones
+        for zone in &footprint.zones {
+            output.push_str(&self.format_zone(zone));
+        }
+
         // Texts
         for text in &footprint.texts {
             output.push_str(&self.format_text(text));
         }
 
+        if self.version >= KicadVersion::V9 {
+            output.push_str("  (embedded_fonts no)\n");
+        }
+
+        // 3D model
+        if let Some(model) = &footprint.model_3d {
+            output.push_str(&self.format_3d_model(model));
+        }
+
+        output.push_str(")\n");
+
+        if self.version >= KicadVersion::V8 {
+            // KiCad 8 writes tab-indented files, one list per line
+            return Ok(SExpr::parse(&output)?.to_pretty_string());
+        }
+
+        Ok(output)
+    }
+
+    /// Export as a KiCad 5 `(module ...)`
+    fn export_v5(&self, footprint: &KiFootprint) -> Result<String> {
+        let mut output = String::new();
+
+        // Module header
+        output.push_str(&format!("(module \"{}\" (layer F.Cu) (tedit 0)\n", footprint.name));
+        output.push_str(&self.format_attr(footprint));
+
+        // Reference and value text
+        output.push_str("  (fp_text reference REF** (at 0 0) (layer F.SilkS)\n");
+        output.push_str("    (effects (font (size 1 1) (thickness 0.15)))\n");
+        output.push_str("  )\n");
+
+        output.push_str(&format!("  (fp_text value \"{}\" (at 0 2.5) (layer F.Fab)\n", footprint.name));
+        output.push_str("    (effects (font (size 1 1) (thickness 0.15)))\n");
+        output.push_str("  )\n");
+
+        // Texts
+        for text in &footprint.texts {
+            output.push_str(&self.format_text_v5(text));
+        }
+
+        // Lines
+        for line in &footprint.lines {
+            output.push_str(&self.format_line_v5(line.start_x, line.start_y, line.end_x, line.end_y, line.width, &line.layer));
+        }
+
+        // Circles
+        for circle in &footprint.circles {
+            output.push_str(&self.format_circle_v5(circle));
+        }
+
+        // Arcs
+        for arc in &footprint.arcs {
+            output.push_str(&self.format_arc_v5(arc));
+        }
+
+        // Rectangles (no fp_rect before KiCad 6)
+        for rect in &footprint.rectangles {
+            output.push_str(&self.format_rectangle_v5(rect));
+        }
+
+        // Polygons
+        for polygon in &footprint.polygons {
+            output.push_str(&self.format_polygon_v5(&polygon.points, polygon.width, &polygon.layer));
+        }
+
+        if !footprint.zones.is_empty() {
+            log::warn!("KiCad 5 footprints cannot hold keepout zones, dropping {} from {}", footprint.zones.len(), footprint.name);
+        }
+
+        // Pads
+        for pad in &footprint.pads {
+            output.push_str(&self.format_pad_v5(pad));
+        }
+
         // 3D model
         if let Some(model) = &footprint.model_3d {
             output.push_str(&self.format_3d_model(model));
@@ -72,26 +199,26 @@ impl FootprintExporter {
         let size_y = self.converter.px_to_mm(pad.size_y);
 
         let mut output = format!(
-            "  (pad \"{}\" {} {} (at {:.4} {:.4}",
+            "  (pad \"{}\" {} {} (at {} {}",
             pad.number,
             pad.pad_type.to_kicad(),
             pad.shape.to_kicad(),
-            x,
-            y
+            Num(x),
+            Num(y)
         );
 
         if pad.rotation != 0.0 {
-            output.push_str(&format!(" {:.4}", pad.rotation));
+            output.push_str(&format!(" {}", Num(pad.rotation)));
         }
 
-        output.push_str(&format!(") (size {:.4} {:.4})", size_x, size_y));
+        output.push_str(&format!(") (size {} {})", Num(size_x), Num(size_y)));
 
         // Layers
         output.push_str(" (layers");
         for layer in &pad.layers {
             output.push_str(&format!(" \"{}\"", layer));
         }
-        output.push_str(")");
+        output.push(')');
 
         // Drill
         if let Some(drill) = &pad.drill {
@@ -99,16 +226,22 @@ impl FootprintExporter {
             if let Some(width) = drill.width {
                 // Elliptical drill
                 let drill_width = self.converter.px_to_mm(width);
-                output.push_str(&format!(" (drill oval {:.4} {:.4})", drill_dia, drill_width));
+                output.push_str(&format!(" (drill oval {} {})", Num(drill_dia), Num(drill_width)));
             } else {
                 // Circular drill
-                output.push_str(&format!(" (drill {:.4})", drill_dia));
+                output.push_str(&format!(" (drill {})", Num(drill_dia)));
             }
         }
 
         // Polygon (for custom pads)
         if let Some(polygon) = &pad.polygon {
-            output.push_str(polygon);
+            output.push_str(" (options (clearance outline) (anchor circle))\n    (primitives\n      (gr_poly\n");
+            output.push_str(&self.format_pts(polygon, "        "));
+            output.push_str("        (width 0) (fill yes))\n    )\n  ");
+        }
+
+        if let Some(uuid) = self.next_uuid() {
+            output.push_str(&format!(" (uuid \"{}\")", uuid));
         }
 
         output.push_str(")\n");
@@ -124,8 +257,8 @@ impl FootprintExporter {
         let width = self.converter.px_to_mm(line.width);
 
         format!(
-            "  (fp_line (start {:.4} {:.4}) (end {:.4} {:.4})\n    (stroke (width {:.4}) (type solid)) (layer \"{}\")\n  )\n",
-            start_x, start_y, end_x, end_y, width, line.layer
+            "  (fp_line (start {} {}) (end {} {})\n    (stroke (width {}) (type solid)) (layer \"{}\")\n{}",
+            Num(start_x), Num(start_y), Num(end_x), Num(end_y), Num(width), line.layer, self.close_item()
         )
     }
 
@@ -139,8 +272,8 @@ impl FootprintExporter {
         let fill = if circle.fill { "solid" } else { "none" };
 
         format!(
-            "  (fp_circle (center {:.4} {:.4}) (end {:.4} {:.4})\n    (stroke (width {:.4}) (type solid)) (fill {}) (layer \"{}\")\n  )\n",
-            center_x, center_y, end_x, end_y, width, fill, circle.layer
+            "  (fp_circle (center {} {}) (end {} {})\n    (stroke (width {}) (type solid)) (fill {}) (layer \"{}\")\n{}",
+            Num(center_x), Num(center_y), Num(end_x), Num(end_y), Num(width), fill, circle.layer, self.close_item()
         )
     }
 
@@ -154,11 +287,79 @@ impl FootprintExporter {
         let width = self.converter.px_to_mm(arc.width);
 
         format!(
-            "  (fp_arc (start {:.4} {:.4}) (mid {:.4} {:.4}) (end {:.4} {:.4})\n    (stroke (width {:.4}) (type solid)) (layer \"{}\")\n  )\n",
-            start_x, start_y, mid_x, mid_y, end_x, end_y, width, arc.layer
+            "  (fp_arc (start {} {}) (mid {} {}) (end {} {})\n    (stroke (width {}) (type solid)) (layer \"{}\")\n{}",
+            Num(start_x), Num(start_y), Num(mid_x), Num(mid_y), Num(end_x), Num(end_y), Num(width), arc.layer, self.close_item()
         )
     }
 
+    fn format_rectangle(&self, rect: &KiRectangle) -> String {
+        let start_x = self.converter.px_to_mm(rect.start_x);
+        let start_y = self.converter.px_to_mm(rect.start_y);  // No flip_y for footprints
+        let end_x = self.converter.px_to_mm(rect.end_x);
+        let end_y = self.converter.px_to_mm(rect.end_y);  // No flip_y for footprints
+        let width = self.converter.px_to_mm(rect.width);
+
+        let fill = if rect.fill { "solid" } else { "none" };
+
+        format!(
+            "  (fp_rect (start {} {}) (end {} {})\n    (stroke (width {}) (type solid)) (fill {}) (layer \"{}\")\n{}",
+            Num(start_x), Num(start_y), Num(end_x), Num(end_y), Num(width), fill, rect.layer, self.close_item()
+        )
+    }
+
+    fn format_polygon(&self, polygon: &KiPolygon) -> String {
+        let width = self.converter.px_to_mm(polygon.width);
+        let fill = if polygon.fill { "solid" } else { "none" };
+
+        format!(
+            "  (fp_poly\n{}    (stroke (width {}) (type solid)) (fill {}) (layer \"{}\")\n{}",
+            self.format_pts(&polygon.points, "    "),
+            Num(width), fill, polygon.layer, self.close_item()
+        )
+    }
+
+    /// EasyEDA cutouts only keep copper pour out; tracks, vias and pads may still cross them
+    fn format_zone(&self, zone: &KiZone) -> String {
+        let layers: Vec<String> = zone.layers.iter().map(|layer| format!("\"{}\"", layer)).collect();
+
+        format!(
+            "  (zone (net 0) (net_name \"\") (layers {}) (hatch edge 0.508)\n    (connect_pads (clearance 0))\n    (min_thickness 0.254)\n    (keepout (tracks allowed) (vias allowed) (pads allowed) (copperpour not_allowed) (footprints allowed))\n    (fill (thermal_gap 0.508) (thermal_bridge_width 0.508))\n    (polygon\n{}    )\n{}",
+            layers.join(" "),
+            self.format_pts(&zone.points, "      "),
+            self.close_item()
+        )
+    }
+
+    /// `(attr ...)` with the footprint type, used for position files and
+    /// the assembly checks; KiCad 5 has no `through_hole` attribute
+    fn format_attr(&self, footprint: &KiFootprint) -> String {
+        let attr = if footprint.pads.iter().any(|pad| pad.pad_type == PadType::ThroughHole) {
+            "through_hole"
+        } else if footprint.pads.iter().any(|pad| pad.pad_type == PadType::Smd) {
+            "smd"
+        } else {
+            return String::new();
+        };
+
+        if self.version.is_legacy() && attr == "through_hole" {
+            return String::new();
+        }
+
+        format!("  (attr {})\n", attr)
+    }
+
+    /// Format a `(pts (xy ...) ...)` block at the given indentation
+    fn format_pts(&self, points: &[(f64, f64)], indent: &str) -> String {
+        let mut output = format!("{}(pts\n", indent);
+        for (x, y) in points {
+            let x = self.converter.px_to_mm(*x);
+            let y = self.converter.px_to_mm(*y);  // No flip_y for footprints
+            output.push_str(&format!("{}  (xy {} {})\n", indent, Num(x), Num(y)));
+        }
+        output.push_str(&format!("{})\n", indent));
+        output
+    }
+
     fn format_text(&self, text: &KiText) -> String {
         let x = self.converter.px_to_mm(text.pos_x);
         let y = self.converter.px_to_mm(text.pos_y);  // No flip_y for footprints
@@ -166,31 +367,315 @@ impl FootprintExporter {
         let thickness = self.converter.px_to_mm(text.thickness);
 
         format!(
-            "  (fp_text user \"{}\" (at {:.4} {:.4}",
-            text.text, x, y
+            "  (fp_text user \"{}\" (at {} {}",
+            text.text, Num(x), Num(y)
         ) + &(if text.rotation != 0.0 {
-            format!(" {:.4}", text.rotation)
+            format!(" {}", Num(text.rotation))
         } else {
             String::new()
         }) + &format!(
-            ") (layer \"{}\")\n    (effects (font (size {:.4} {:.4}) (thickness {:.4})))\n  )\n",
-            text.layer, size, size, thickness
+            ") (layer \"{}\")\n    (effects (font (size {} {}) (thickness {})))\n{}",
+            text.layer, Num(size), Num(size), Num(thickness), self.close_item()
         )
     }
 
-    fn format_3d_model(&self, model: &Ki3dModel) -> String {
+    /// Format a KiCad 8+ footprint field
+    fn format_property(&self, name: &str, value: &str, y: f64, layer: &str, hidden: bool) -> String {
+        let size = if hidden { "1.27" } else { "1" };
         format!(
-            "  (model \"{}\"\n    (offset (xyz {:.4} {:.4} {:.4}))\n    (scale (xyz {:.4} {:.4} {:.4}))\n    (rotate (xyz {:.4} {:.4} {:.4}))\n  )\n",
-            model.path,
-            model.offset.0, model.offset.1, model.offset.2,
-            model.scale.0, model.scale.1, model.scale.2,
-            model.rotate.0, model.rotate.1, model.rotate.2
+            "  (property \"{}\" \"{}\" (at 0 {} 0) (layer \"{}\"){}\n    (effects (font (size {} {}) (thickness 0.15)))\n{}",
+            name, value, y, layer,
+            if hidden { " (hide yes)" } else { "" },
+            size, size,
+            self.close_item()
+        )
+    }
+
+    /// Closing line of a multi-line item, with its UUID on KiCad 8+
+    fn close_item(&self) -> String {
+        match self.next_uuid() {
+            Some(uuid) => format!("    (uuid \"{}\")\n  )\n", uuid),
+            None => "  )\n".to_string(),
+        }
+    }
+
+    /// Next item UUID, derived from the footprint name and item order so
+    /// re-exporting an unchanged footprint gives identical files
+    fn next_uuid(&self) -> Option<String> {
+        if self.version < KicadVersion::V8 {
+            return None;
+        }
+
+        let index = self.uuid_count.get();
+        self.uuid_count.set(index + 1);
+
+        let key = index.to_le_bytes();
+        let high = fnv1a(self.uuid_seed.get(), &key);
+        let low = fnv1a(high, &key);
+
+        // RFC 9562 version 8 (custom) layout
+        let high = (high & !0xf000) | 0x8000;
+        let low = (low & !(0b11 << 62)) | (0b10 << 62);
+        Some(format!(
+            "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+            high >> 32,
+            (high >> 16) & 0xffff,
+            high & 0xffff,
+            low >> 48,
+            low & 0xffff_ffff_ffff
+        ))
+    }
+
+    fn format_pad_v5(&self, pad: &KiPad) -> String {
+        let x = self.converter.px_to_mm(pad.pos_x);
+        let y = self.converter.px_to_mm(pad.pos_y);
+        let size_x = self.converter.px_to_mm(pad.size_x);
+        let size_y = self.converter.px_to_mm(pad.size_y);
+
+        let mut output = format!(
+            "  (pad \"{}\" {} {} (at {} {}",
+            pad.number,
+            pad.pad_type.to_kicad(),
+            pad.shape.to_kicad(),
+            Num(x),
+            Num(y)
+        );
+
+        if pad.rotation != 0.0 {
+            output.push_str(&format!(" {}", Num(pad.rotation)));
+        }
+
+        output.push_str(&format!(") (size {} {})", Num(size_x), Num(size_y)));
+
+        // Drill
+        if let Some(drill) = &pad.drill {
+            let drill_dia = self.converter.px_to_mm(drill.diameter);
+            match drill.width {
+                Some(width) => {
+                    output.push_str(&format!(" (drill oval {} {})", Num(drill_dia), Num(self.converter.px_to_mm(width))));
+                }
+                None => output.push_str(&format!(" (drill {})", Num(drill_dia))),
+            }
+        }
+
+        // Layers, unquoted
+        output.push_str(&format!(" (layers {})", pad.layers.join(" ")));
+
+        // Polygon (for custom pads); primitives have no fill flag before KiCad 6
+        if let Some(polygon) = &pad.polygon {
+            output.push_str(" (options (clearance outline) (anchor circle))\n    (primitives\n      (gr_poly\n");
+            output.push_str(&self.format_pts(polygon, "        "));
+            output.push_str("        (width 0))\n    )\n  ");
+        }
+
+        output.push_str(")\n");
+
+        output
+    }
+
+    fn format_line_v5(&self, start_x: f64, start_y: f64, end_x: f64, end_y: f64, width: f64, layer: &str) -> String {
+        format!(
+            "  (fp_line (start {} {}) (end {} {}) (layer {}) (width {}))\n",
+            Num(self.converter.px_to_mm(start_x)),
+            Num(self.converter.px_to_mm(start_y)),
+            Num(self.converter.px_to_mm(end_x)),
+            Num(self.converter.px_to_mm(end_y)),
+            layer,
+            Num(self.converter.px_to_mm(width))
+        )
+    }
+
+    fn format_circle_v5(&self, circle: &KiCircle) -> String {
+        format!(
+            "  (fp_circle (center {} {}) (end {} {}) (layer {}) (width {}))\n",
+            Num(self.converter.px_to_mm(circle.center_x)),
+            Num(self.converter.px_to_mm(circle.center_y)),
+            Num(self.converter.px_to_mm(circle.end_x)),
+            Num(self.converter.px_to_mm(circle.end_y)),
+            circle.layer,
+            Num(self.converter.px_to_mm(circle.width))
         )
     }
+
+    /// KiCad 5 arcs are stored as center, start point and sweep angle
+    /// (positive is clockwise on screen)
+    fn format_arc_v5(&self, arc: &KiArc) -> String {
+        let start = (arc.start_x, arc.start_y);
+        let mid = (arc.mid_x, arc.mid_y);
+        let end = (arc.end_x, arc.end_y);
+
+        let Some((cx, cy, _)) = self.converter.arc_center_from_points(start, mid, end) else {
+            // Degenerate arc, draw it as a straight line
+            return self.format_line_v5(arc.start_x, arc.start_y, arc.end_x, arc.end_y, arc.width, &arc.layer);
+        };
+
+        let angle_of = |(x, y): (f64, f64)| self.converter.rad_to_deg((y - cy).atan2(x - cx));
+        let to_mid = (angle_of(mid) - angle_of(start)).rem_euclid(360.0);
+        let to_end = (angle_of(end) - angle_of(start)).rem_euclid(360.0);
+        let sweep = if to_mid < to_end { to_end } else { to_end - 360.0 };
+
+        format!(
+            "  (fp_arc (start {} {}) (end {} {}) (angle {}) (layer {}) (width {}))\n",
+            Num(self.converter.px_to_mm(cx)),
+            Num(self.converter.px_to_mm(cy)),
+            Num(self.converter.px_to_mm(arc.start_x)),
+            Num(self.converter.px_to_mm(arc.start_y)),
+            Num(sweep),
+            arc.layer,
+            Num(self.converter.px_to_mm(arc.width))
+        )
+    }
+
+    fn format_rectangle_v5(&self, rect: &KiRectangle) -> String {
+        let corners = [
+            (rect.start_x, rect.start_y),
+            (rect.end_x, rect.start_y),
+            (rect.end_x, rect.end_y),
+            (rect.start_x, rect.end_y),
+        ];
+
+        if rect.fill {
+            return self.format_polygon_v5(&corners, rect.width, &rect.layer);
+        }
+
+        (0..4).map(|i| {
+            let (start, end) = (corners[i], corners[(i + 1) % 4]);
+            self.format_line_v5(start.0, start.1, end.0, end.1, rect.width, &rect.layer)
+        }).collect()
+    }
+
+    fn format_polygon_v5(&self, points: &[(f64, f64)], width: f64, layer: &str) -> String {
+        format!(
+            "  (fp_poly\n{}    (layer {}) (width {})\n  )\n",
+            self.format_pts(points, "    "),
+            layer,
+            Num(self.converter.px_to_mm(width))
+        )
+    }
+
+    fn format_text_v5(&self, text: &KiText) -> String {
+        let x = self.converter.px_to_mm(text.pos_x);
+        let y = self.converter.px_to_mm(text.pos_y);
+        let size = self.converter.px_to_mm(text.size);
+        let thickness = self.converter.px_to_mm(text.thickness);
+        let rotation = if text.rotation != 0.0 { format!(" {}", Num(text.rotation)) } else { String::new() };
+
+        format!(
+            "  (fp_text user \"{}\" (at {} {}{}) (layer {})\n    (effects (font (size {} {}) (thickness {})))\n  )\n",
+            text.text, Num(x), Num(y), rotation, text.layer, Num(size), Num(size), Num(thickness)
+        )
+    }
+
+    /// `(model ...)` block, also used to add a model to an existing footprint
+    pub fn format_3d_model(&self, model: &Ki3dModel) -> String {
+        let block = format!(
+            "  (model \"{}\"\n    (offset (xyz {} {} {}))\n    (scale (xyz {} {} {}))\n    (rotate (xyz {} {} {}))\n  )\n",
+            model.path,
+            Num(model.offset.0), Num(model.offset.1), Num(model.offset.2),
+            Num(model.scale.0), Num(model.scale.1), Num(model.scale.2),
+            Num(model.rotate.0), Num(model.rotate.1), Num(model.rotate.2)
+        );
+
+        if self.version < KicadVersion::V8 {
+            return block;
+        }
+
+        // Tab-indented as in a KiCad 8 file, one level inside the footprint
+        let block = SExpr::parse(&block).expect("model block is a single expression").to_pretty_string();
+        block.lines().map(|line| format!("\t{}\n", line)).collect()
+    }
 }
 
 impl Default for FootprintExporter {
     fn default() -> Self {
-        Self::new()
+        Self::new(KicadVersion::V6)
+    }
+}
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// 64-bit FNV-1a hash, continuing from `hash`
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, &byte| (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn footprint() -> KiFootprint {
+        KiFootprint {
+            name: "TEST".to_string(),
+            pads: Vec::new(),
+            tracks: Vec::new(),
+            circles: Vec::new(),
+            arcs: Vec::new(),
+            rectangles: Vec::new(),
+            polygons: Vec::new(),
+            zones: Vec::new(),
+            texts: Vec::new(),
+            lines: Vec::new(),
+            model_3d: None,
+        }
+    }
+
+    fn header(version: KicadVersion) -> String {
+        let output = FootprintExporter::new(version).export(&footprint()).unwrap();
+        output.lines().next().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_header_version() {
+        assert_eq!(header(KicadVersion::V6), "(footprint \"TEST\" (version 20211014) (generator nlbn)");
+        assert_eq!(header(KicadVersion::V7), "(footprint \"TEST\" (version 20221018) (generator nlbn)");
+    }
+
+    #[test]
+    fn test_attr_follows_pad_types() {
+        let attr = |pad_types: &[PadType], version| {
+            let mut fp = footprint();
+            for &pad_type in pad_types {
+                fp.pads.push(KiPad {
+                    number: "1".to_string(),
+                    pad_type,
+                    shape: PadShape::Circle,
+                    pos_x: 0.0,
+                    pos_y: 0.0,
+                    size_x: 4.0,
+                    size_y: 4.0,
+                    rotation: 0.0,
+                    layers: vec!["F.Cu".to_string()],
+                    drill: None,
+                    polygon: None,
+                });
+            }
+            let output = FootprintExporter::new(version).export(&fp).unwrap();
+            SExpr::parse(&output).unwrap().find("attr").map(SExpr::to_compact_string)
+        };
+
+        assert_eq!(attr(&[PadType::Smd, PadType::NpThroughHole], KicadVersion::V8).as_deref(), Some("(attr smd)"));
+        assert_eq!(attr(&[PadType::Smd, PadType::ThroughHole], KicadVersion::V6).as_deref(), Some("(attr through_hole)"));
+        assert_eq!(attr(&[PadType::ThroughHole], KicadVersion::V5), None);
+        assert_eq!(attr(&[], KicadVersion::V9), None);
+    }
+
+    #[test]
+    fn test_cutout_only_keeps_out_copper_pour() {
+        let mut fp = footprint();
+        fp.zones.push(KiZone {
+            points: vec![(0.0, 0.0), (10.0, 0.0), (10.0, 10.0)],
+            layers: vec!["F.Cu".to_string(), "B.Cu".to_string()],
+        });
+
+        let output = FootprintExporter::new(KicadVersion::V6).export(&fp).unwrap();
+        let root = SExpr::parse(&output).unwrap();
+        let keepout = root.find("zone").and_then(|zone| zone.find("keepout")).unwrap();
+        let rule = |name: &str| keepout.find(name)?.items()?.get(1)?.as_str().map(str::to_string);
+
+        assert_eq!(rule("copperpour").as_deref(), Some("not_allowed"));
+        assert_eq!(rule("tracks").as_deref(), Some("allowed"));
+        assert_eq!(rule("vias").as_deref(), Some("allowed"));
+        assert_eq!(rule("pads").as_deref(), Some("allowed"));
+        assert_eq!(rule("footprints").as_deref(), Some("allowed"));
     }
 }
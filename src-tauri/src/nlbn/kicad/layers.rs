@@ -1,5 +1,5 @@
-/// KiCad layer mapping for EasyEDA footprints
-/// Based on easyeda2kicad.py layer mapping
+//! KiCad layer mapping for EasyEDA footprints
+//! Based on easyeda2kicad.py layer mapping
 
 /// Map EasyEDA layer ID to KiCad layer name for general graphics
 pub fn map_layer(layer_id: i32) -> String {
@@ -23,6 +23,17 @@ pub fn map_layer(layer_id: i32) -> String {
     }
 }
 
+/// Map EasyEDA layer ID to a KiCad layer for footprint graphics
+///
+/// Returns None for EasyEDA-only layers (ratlines, 3D model outline,
+/// component/lead shapes) that have no KiCad counterpart.
+pub fn map_graphic_layer(layer_id: i32) -> Option<String> {
+    match layer_id {
+        9 | 19 | 99 | 100 => None,
+        _ => Some(map_layer(layer_id)),
+    }
+}
+
 /// Map EasyEDA layer ID to KiCad pad layers for SMD pads
 pub fn map_pad_layers_smd(layer_id: i32) -> Vec<String> {
     match layer_id {
@@ -62,6 +73,14 @@ mod tests {
         assert_eq!(map_layer(13), "F.Fab");
     }
 
+    #[test]
+    fn test_map_graphic_layer() {
+        assert_eq!(map_graphic_layer(3).as_deref(), Some("F.SilkS"));
+        assert_eq!(map_graphic_layer(15).as_deref(), Some("Dwgs.User"));
+        assert_eq!(map_graphic_layer(19), None);
+        assert_eq!(map_graphic_layer(99), None);
+    }
+
     #[test]
     fn test_map_pad_layers_smd() {
         let layers = map_pad_layers_smd(1);
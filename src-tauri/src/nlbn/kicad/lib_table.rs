@@ -0,0 +1,162 @@
+//! Editing of KiCad `sym-lib-table` and `fp-lib-table` files
+
+use super::super::error::{KicadError, Result};
+use super::super::KicadVersion;
+use super::sexpr::SExpr;
+
+/// Which library table a file is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LibTableKind {
+    Symbol,
+    Footprint,
+}
+
+impl LibTableKind {
+    /// File name of the table in a project or config directory
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            LibTableKind::Symbol => "sym-lib-table",
+            LibTableKind::Footprint => "fp-lib-table",
+        }
+    }
+
+    fn keyword(&self) -> &'static str {
+        match self {
+            LibTableKind::Symbol => "sym_lib_table",
+            LibTableKind::Footprint => "fp_lib_table",
+        }
+    }
+}
+
+/// A parsed `(sym_lib_table ...)` or `(fp_lib_table ...)` file
+#[derive(Debug, Clone)]
+pub struct LibTable {
+    root: SExpr,
+}
+
+impl LibTable {
+    /// Create an empty table; KiCad 7 and later write a `(version 7)` header
+    pub fn new(kind: LibTableKind, version: KicadVersion) -> Self {
+        let mut items = Vec::new();
+        if version >= KicadVersion::V7 {
+            items.push(SExpr::list("version", vec![SExpr::atom("7")]));
+        }
+
+        Self {
+            root: SExpr::list(kind.keyword(), items),
+        }
+    }
+
+    /// Parse the content of a table file
+    pub fn parse(kind: LibTableKind, content: &str) -> Result<Self> {
+        let root = SExpr::parse(content)?;
+        if root.head() != Some(kind.keyword()) {
+            return Err(KicadError::Parse(format!("not a {} file", kind.file_name())).into());
+        }
+
+        Ok(Self { root })
+    }
+
+    fn libs(&self) -> impl Iterator<Item = &SExpr> {
+        self.root.items().unwrap_or_default().iter().filter(|item| item.head() == Some("lib"))
+    }
+
+    /// Nicknames of all libraries in the table
+    pub fn names(&self) -> Vec<String> {
+        self.libs().filter_map(|lib| lib_field(lib, "name")).map(str::to_string).collect()
+    }
+
+    /// URI registered for a nickname
+    pub fn uri(&self, name: &str) -> Option<&str> {
+        self.libs()
+            .find(|lib| lib_field(lib, "name") == Some(name))
+            .and_then(|lib| lib_field(lib, "uri"))
+    }
+
+    /// Add a library unless its nickname or URI is already registered
+    ///
+    /// Existing entries are never modified. Returns true if an entry was added.
+    pub fn add(&mut self, name: &str, lib_type: &str, uri: &str, descr: &str) -> bool {
+        let registered = self.libs().any(|lib| {
+            lib_field(lib, "name") == Some(name) || lib_field(lib, "uri") == Some(uri)
+        });
+        if registered {
+            return false;
+        }
+
+        let field = |key: &str, value: &str| SExpr::list(key, vec![SExpr::string(value)]);
+        let lib = SExpr::list("lib", vec![
+            field("name", name),
+            field("type", lib_type),
+            field("uri", uri),
+            field("options", ""),
+            field("descr", descr),
+        ]);
+
+        self.root.items_mut().expect("table root is a list").push(lib);
+        true
+    }
+
+    /// Serialize in KiCad's layout: one entry per line
+    pub fn to_string_pretty(&self) -> String {
+        let items = self.root.items().unwrap_or_default();
+        let mut out = String::new();
+
+        out.push('(');
+        out.push_str(self.root.head().unwrap_or_default());
+        out.push('\n');
+        for item in items.iter().skip(1) {
+            out.push_str("  ");
+            out.push_str(&item.to_compact_string());
+            out.push('\n');
+        }
+        out.push_str(")\n");
+
+        out
+    }
+}
+
+/// Value of a `(key "value")` field of a `(lib ...)` entry
+fn lib_field<'a>(lib: &'a SExpr, key: &str) -> Option<&'a str> {
+    lib.find(key)?.items()?.get(1)?.as_str()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXISTING: &str = "(fp_lib_table\n  (version 7)\n  (lib (name \"Local\")(type \"KiCad\")(uri \"${KIPRJMOD}/local.pretty\")(options \"\")(descr \"Hand-made\"))\n)\n";
+
+    #[test]
+    fn test_add_is_idempotent() {
+        let mut table = LibTable::parse(LibTableKind::Footprint, EXISTING).unwrap();
+
+        assert!(table.add("nlbn", "KiCad", "${KIPRJMOD}/nlbn.pretty", ""));
+        assert!(!table.add("nlbn", "KiCad", "${KIPRJMOD}/nlbn.pretty", ""));
+        assert_eq!(table.names(), vec!["Local", "nlbn"]);
+
+        let reparsed = LibTable::parse(LibTableKind::Footprint, &table.to_string_pretty()).unwrap();
+        assert_eq!(reparsed.uri("nlbn"), Some("${KIPRJMOD}/nlbn.pretty"));
+        assert_eq!(reparsed.uri("Local"), Some("${KIPRJMOD}/local.pretty"));
+    }
+
+    #[test]
+    fn test_never_clobbers_existing_entries() {
+        let mut table = LibTable::parse(LibTableKind::Footprint, EXISTING).unwrap();
+
+        // Same nickname pointing elsewhere, and same URI under another nickname
+        assert!(!table.add("Local", "KiCad", "/tmp/other.pretty", ""));
+        assert!(!table.add("nlbn", "KiCad", "${KIPRJMOD}/local.pretty", ""));
+        assert_eq!(table.uri("Local"), Some("${KIPRJMOD}/local.pretty"));
+        assert_eq!(table.names(), vec!["Local"]);
+    }
+
+    #[test]
+    fn test_new_and_wrong_kind() {
+        let table = LibTable::new(LibTableKind::Symbol, KicadVersion::V8);
+        assert_eq!(table.to_string_pretty(), "(sym_lib_table\n  (version 7)\n)\n");
+        assert_eq!(LibTable::new(LibTableKind::Symbol, KicadVersion::V6).to_string_pretty(), "(sym_lib_table\n)\n");
+
+        assert!(LibTable::parse(LibTableKind::Symbol, EXISTING).is_err());
+    }
+}
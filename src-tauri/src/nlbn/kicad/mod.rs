@@ -4,6 +4,9 @@ pub mod symbol_exporter;
 pub mod footprint_exporter;
 pub mod model_exporter;
 pub mod layers;
+pub mod sexpr;
+pub mod symbol_lib;
+pub mod lib_table;
 
 pub use symbol::{KiSymbol, KiPin, KiRectangle, KiCircle, KiPolyline, PinType, PinStyle};
 pub use footprint::{
@@ -11,9 +14,14 @@ pub use footprint::{
     PadType, PadShape,
     KiCircle as FootprintKiCircle,
     KiArc as FootprintKiArc,
+    KiRectangle as FootprintKiRectangle,
+    KiPolygon, KiZone,
 };
-pub use symbol::KiArc as SymbolKiArc;
+pub use symbol::{KiArc as SymbolKiArc, KiText as SymbolKiText, PinMove};
 pub use symbol_exporter::SymbolExporter;
 pub use footprint_exporter::FootprintExporter;
 pub use model_exporter::ModelExporter;
 pub use layers::*;
+pub use sexpr::{Num, SExpr};
+pub use symbol_lib::SymbolLibrary;
+pub use lib_table::{LibTable, LibTableKind};
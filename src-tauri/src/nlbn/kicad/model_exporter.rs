@@ -34,7 +34,7 @@ impl ModelExporter {
 
             // First line is the material name
             let material_name = lines[0].trim();
-            let material = materials.get(material_name).cloned().unwrap_or_else(|| Material {
+            let material = materials.get(material_name).cloned().unwrap_or(Material {
                 diffuse: (0.8, 0.8, 0.8),
                 specular: (0.5, 0.5, 0.5),
             });
@@ -57,8 +57,8 @@ impl ModelExporter {
                         let index_str = part.replace("//", "");
                         if let Ok(index) = index_str.parse::<i32>() {
                             // Check if we've seen this vertex before
-                            if !link_dict.contains_key(&index) {
-                                link_dict.insert(index, index_counter);
+                            if let std::collections::hash_map::Entry::Vacant(e) = link_dict.entry(index) {
+                                e.insert(index_counter);
                                 face_index.push(index_counter);
                                 // Add vertex (OBJ indices are 1-based)
                                 if (index as usize) <= vertices.len() {
@@ -83,7 +83,7 @@ impl ModelExporter {
             }
 
             // Duplicate last point (Python does this: points.insert(-1, points[-1]))
-            if points.len() > 0 {
+            if !points.is_empty() {
                 let last = points[points.len() - 1].clone();
                 points.insert(points.len() - 1, last);
             }
@@ -133,7 +133,7 @@ impl ModelExporter {
                 if i < coord_index.len() - 1 {
                     output.push_str(",\n");
                 } else {
-                    output.push_str("\n");
+                    output.push('\n');
                 }
             }
 
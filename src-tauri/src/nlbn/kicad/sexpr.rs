@@ -0,0 +1,361 @@
+//! Minimal S-expression reader/writer for KiCad files
+//!
+//! Numbers and keywords are kept verbatim as atoms, so a parse/serialize
+//! round trip never changes values - only whitespace.
+
+use super::super::error::{AppError, KicadError, Result};
+
+/// Maximum line length when packing consecutive `(xy ...)` points on one line
+const XY_LINE_LIMIT: usize = 99;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SExpr {
+    /// Unquoted token (keyword, number, `yes`/`no`, ...)
+    Atom(String),
+    /// Quoted string (stored unescaped)
+    Str(String),
+    List(Vec<SExpr>),
+}
+
+impl SExpr {
+    /// Parse a single S-expression (surrounding whitespace is allowed)
+    pub fn parse(input: &str) -> Result<SExpr> {
+        let mut parser = Parser { chars: input.chars().collect(), pos: 0, line: 1 };
+
+        parser.skip_whitespace();
+        let expr = parser.parse_expr()?;
+        parser.skip_whitespace();
+
+        if parser.pos < parser.chars.len() {
+            return Err(parser.error("unexpected content after the closing parenthesis"));
+        }
+
+        Ok(expr)
+    }
+
+    pub fn atom(value: &str) -> SExpr {
+        SExpr::Atom(value.to_string())
+    }
+
+    pub fn string(value: &str) -> SExpr {
+        SExpr::Str(value.to_string())
+    }
+
+    /// Build a list from a keyword and its arguments, e.g. `(version 20211014)`
+    pub fn list(keyword: &str, items: Vec<SExpr>) -> SExpr {
+        let mut list = Vec::with_capacity(items.len() + 1);
+        list.push(SExpr::atom(keyword));
+        list.extend(items);
+        SExpr::List(list)
+    }
+
+    /// Text of an atom or string
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            SExpr::Atom(s) | SExpr::Str(s) => Some(s),
+            SExpr::List(_) => None,
+        }
+    }
+
+    pub fn items(&self) -> Option<&[SExpr]> {
+        match self {
+            SExpr::List(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn items_mut(&mut self) -> Option<&mut Vec<SExpr>> {
+        match self {
+            SExpr::List(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    /// Keyword of a list, e.g. `symbol` for `(symbol "R" ...)`
+    pub fn head(&self) -> Option<&str> {
+        match self.items()?.first()? {
+            SExpr::Atom(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// First child list with the given keyword
+    pub fn find(&self, keyword: &str) -> Option<&SExpr> {
+        self.items()?.iter().find(|item| item.head() == Some(keyword))
+    }
+
+    fn has_list_child(&self) -> bool {
+        self.items().is_some_and(|items| items.iter().any(|i| matches!(i, SExpr::List(_))))
+    }
+
+    /// Serialize on a single line
+    pub fn to_compact_string(&self) -> String {
+        let mut out = String::new();
+        self.write_compact(&mut out);
+        out
+    }
+
+    fn write_compact(&self, out: &mut String) {
+        match self {
+            SExpr::Atom(s) => out.push_str(s),
+            SExpr::Str(s) => write_quoted(out, s),
+            SExpr::List(items) => {
+                out.push('(');
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        out.push(' ');
+                    }
+                    item.write_compact(out);
+                }
+                out.push(')');
+            }
+        }
+    }
+
+    /// Serialize in KiCad's canonical layout: every child list on its own
+    /// tab-indented line, lists without child lists kept inline, and runs of
+    /// `(xy ...)` points packed together.
+    pub fn to_pretty_string(&self) -> String {
+        let mut out = String::new();
+        self.write_pretty(&mut out, 0);
+        out.push('\n');
+        out
+    }
+
+    fn write_pretty(&self, out: &mut String, depth: usize) {
+        let items = match self {
+            SExpr::List(items) if self.has_list_child() => items,
+            _ => {
+                self.write_compact(out);
+                return;
+            }
+        };
+
+        out.push('(');
+        let mut prev_xy = false;
+
+        for (i, item) in items.iter().enumerate() {
+            match item {
+                SExpr::List(_) => {
+                    let is_xy = item.head() == Some("xy") && !item.has_list_child();
+                    let line_len = out.len() - out.rfind('\n').map_or(0, |p| p + 1);
+                    let fits = line_len + 1 + item.to_compact_string().len() <= XY_LINE_LIMIT;
+
+                    if is_xy && prev_xy && fits {
+                        out.push(' ');
+                    } else {
+                        out.push('\n');
+                        push_indent(out, depth + 1);
+                    }
+
+                    item.write_pretty(out, depth + 1);
+                    prev_xy = is_xy;
+                }
+                _ => {
+                    if i > 0 {
+                        out.push(' ');
+                    }
+                    item.write_compact(out);
+                    prev_xy = false;
+                }
+            }
+        }
+
+        out.push('\n');
+        push_indent(out, depth);
+        out.push(')');
+    }
+}
+
+fn push_indent(out: &mut String, depth: usize) {
+    for _ in 0..depth {
+        out.push('\t');
+    }
+}
+
+fn write_quoted(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            _ => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+}
+
+impl Parser {
+    fn error(&self, message: &str) -> AppError {
+        KicadError::Parse(format!("line {}: {}", self.line, message)).into()
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        if c == '\n' {
+            self.line += 1;
+        }
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|c| c.is_whitespace()) {
+            self.bump();
+        }
+    }
+
+    fn parse_expr(&mut self) -> Result<SExpr> {
+        match self.peek() {
+            None => Err(self.error("unexpected end of input")),
+            Some('(') => self.parse_list(),
+            Some(')') => Err(self.error("unexpected ')'")),
+            Some('"') => self.parse_string(),
+            Some(_) => Ok(self.parse_atom()),
+        }
+    }
+
+    fn parse_list(&mut self) -> Result<SExpr> {
+        let start_line = self.line;
+        self.bump(); // '('
+        let mut items = Vec::new();
+
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                None => {
+                    return Err(KicadError::Parse(format!(
+                        "line {}: unclosed '(' (file is truncated?)",
+                        start_line
+                    )).into());
+                }
+                Some(')') => {
+                    self.bump();
+                    return Ok(SExpr::List(items));
+                }
+                Some(_) => items.push(self.parse_expr()?),
+            }
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<SExpr> {
+        let start_line = self.line;
+        self.bump(); // opening quote
+        let mut value = String::new();
+
+        loop {
+            match self.bump() {
+                None => {
+                    return Err(KicadError::Parse(format!("line {}: unterminated string", start_line)).into());
+                }
+                Some('"') => return Ok(SExpr::Str(value)),
+                Some('\\') => match self.bump() {
+                    Some('n') => value.push('\n'),
+                    Some('t') => value.push('\t'),
+                    Some(c) => value.push(c),
+                    None => {
+                        return Err(KicadError::Parse(format!("line {}: unterminated string", start_line)).into());
+                    }
+                },
+                Some(c) => value.push(c),
+            }
+        }
+    }
+
+    fn parse_atom(&mut self) -> SExpr {
+        let mut value = String::new();
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() || c == '(' || c == ')' || c == '"' {
+                break;
+            }
+            value.push(c);
+            self.bump();
+        }
+        SExpr::Atom(value)
+    }
+}
+
+/// A number written the way KiCad writes it: shortest form, rounded to
+/// 0.1 µm, without trailing zeros (`1.27`, `0`, `-0.5`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Num(pub f64);
+
+impl std::fmt::Display for Num {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = format!("{:.4}", self.0);
+        let text = text.trim_end_matches('0').trim_end_matches('.');
+        f.write_str(if text == "-0" { "0" } else { text })
+    }
+}
+
+impl std::fmt::Display for SExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.to_compact_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_atoms_strings_and_lists() {
+        let expr = SExpr::parse(r#" (property "Value" "10k \"x\"" (at 0 -2.54 0)) "#).unwrap();
+        assert_eq!(expr.head(), Some("property"));
+        let items = expr.items().unwrap();
+        assert_eq!(items[1], SExpr::string("Value"));
+        assert_eq!(items[2], SExpr::string("10k \"x\""));
+        assert_eq!(expr.find("at").unwrap().to_compact_string(), "(at 0 -2.54 0)");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(SExpr::parse("(kicad_symbol_lib (version 1)").is_err());
+        assert!(SExpr::parse("(a))").is_err());
+        assert!(SExpr::parse("(a \"unterminated)").is_err());
+        assert!(SExpr::parse("").is_err());
+    }
+
+    #[test]
+    fn test_pretty_layout() {
+        let expr = SExpr::parse(
+            "(polyline (pts (xy 0 0) (xy 1.27 0) (xy 1.27 1.27)) (stroke (width 0) (type default)) (fill (type none)))"
+        ).unwrap();
+        assert_eq!(
+            expr.to_pretty_string(),
+            "(polyline\n\t(pts\n\t\t(xy 0 0) (xy 1.27 0) (xy 1.27 1.27)\n\t)\n\t(stroke\n\t\t(width 0)\n\t\t(type default)\n\t)\n\t(fill\n\t\t(type none)\n\t)\n)\n"
+        );
+    }
+
+    #[test]
+    fn test_number_format() {
+        assert_eq!(Num(1.27).to_string(), "1.27");
+        assert_eq!(Num(2.0).to_string(), "2");
+        assert_eq!(Num(0.0).to_string(), "0");
+        assert_eq!(Num(-0.00001).to_string(), "0");
+        assert_eq!(Num(-0.5).to_string(), "-0.5");
+        assert_eq!(Num(0.99999).to_string(), "1");
+        assert_eq!(Num(10.0).to_string(), "10");
+        assert_eq!(Num(0.12344).to_string(), "0.1234");
+    }
+
+    #[test]
+    fn test_round_trip_preserves_values() {
+        let text = "(kicad_symbol_lib (version 20211014) (generator nlbn) (symbol \"R\" (pin_names (offset 0)) (property \"Reference\" \"R\" (id 0) (at 2.032 0 90))))";
+        let expr = SExpr::parse(text).unwrap();
+        let reparsed = SExpr::parse(&expr.to_pretty_string()).unwrap();
+        assert_eq!(expr, reparsed);
+        assert_eq!(reparsed.to_compact_string(), text);
+    }
+}
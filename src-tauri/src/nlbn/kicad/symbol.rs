@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PinType {
     Input,
@@ -14,8 +16,14 @@ pub enum PinType {
 }
 
 impl PinType {
+    /// Map the electric type field of an EasyEDA pin (`0`-`4`, or a KiCad 5 letter)
     pub fn from_easyeda(electric_type: &str) -> Self {
         match electric_type {
+            "0" => PinType::Unspecified,
+            "1" => PinType::Input,
+            "2" => PinType::Output,
+            "3" => PinType::Bidirectional,
+            "4" => PinType::PowerIn,
             "I" => PinType::Input,
             "O" => PinType::Output,
             "B" => PinType::Bidirectional,
@@ -112,16 +120,121 @@ pub struct KiSymbol {
     pub name: String,
     pub reference: String,
     pub value: String,
+    /// Linked footprint as `<library>:<footprint>`, empty if none
     pub footprint: String,
+    /// Footprint name patterns offered for this symbol (`ki_fp_filters`)
+    pub fp_filters: Vec<String>,
     pub datasheet: String,
     pub manufacturer: String,
     pub lcsc_id: String,
     pub jlc_id: String,
+    /// Number of units; every graphic item carries the unit it belongs to
+    pub unit_count: u32,
     pub pins: Vec<KiPin>,
     pub rectangles: Vec<KiRectangle>,
     pub circles: Vec<KiCircle>,
     pub arcs: Vec<KiArc>,
     pub polylines: Vec<KiPolyline>,
+    pub texts: Vec<KiText>,
+}
+
+impl KiSymbol {
+    /// Move every item of every unit by `(dx, dy)`
+    pub fn translate(&mut self, dx: f64, dy: f64) {
+        for pin in &mut self.pins {
+            pin.pos_x += dx;
+            pin.pos_y += dy;
+        }
+        for rect in &mut self.rectangles {
+            rect.x1 += dx;
+            rect.y1 += dy;
+            rect.x2 += dx;
+            rect.y2 += dy;
+        }
+        for circle in &mut self.circles {
+            circle.cx += dx;
+            circle.cy += dy;
+        }
+        for arc in &mut self.arcs {
+            arc.start_x += dx;
+            arc.start_y += dy;
+            arc.mid_x += dx;
+            arc.mid_y += dy;
+            arc.end_x += dx;
+            arc.end_y += dy;
+        }
+        for polyline in &mut self.polylines {
+            for (x, y) in polyline.points.iter_mut() {
+                *x += dx;
+                *y += dy;
+            }
+        }
+        for text in &mut self.texts {
+            text.pos_x += dx;
+            text.pos_y += dy;
+        }
+    }
+}
+
+/// A pin moved relative to the symbol body by grid snapping
+#[derive(Debug, Clone, PartialEq)]
+pub struct PinMove {
+    pub number: String,
+    pub unit: u32,
+    pub dx: f64,
+    pub dy: f64,
+}
+
+impl KiSymbol {
+    /// Snap pin connection points to `grid`.
+    ///
+    /// The whole symbol is first shifted by the offset most pins share, so a
+    /// consistently off-grid symbol moves as one piece. Pins still off the
+    /// grid then move individually; their length changes so the body end
+    /// stays where it was. Returns the pins moved relative to the body.
+    pub fn snap_pins_to_grid(&mut self, grid: f64) -> Vec<PinMove> {
+        let residual = |v: f64| v - (v / grid).round() * grid;
+
+        // Most common off-grid offset, quantized to tolerate float noise
+        let mut counts: HashMap<(i64, i64), (usize, f64, f64)> = HashMap::new();
+        for pin in &self.pins {
+            let (rx, ry) = (residual(pin.pos_x), residual(pin.pos_y));
+            let key = ((rx * 1e6).round() as i64, (ry * 1e6).round() as i64);
+            counts.entry(key).or_insert((0, rx, ry)).0 += 1;
+        }
+        let shift = counts.values()
+            .max_by(|a, b| {
+                a.0.cmp(&b.0)
+                    .then(b.1.abs().total_cmp(&a.1.abs()))
+                    .then(b.2.abs().total_cmp(&a.2.abs()))
+            })
+            .map(|&(_, rx, ry)| (rx, ry));
+        if let Some((rx, ry)) = shift {
+            if rx != 0.0 || ry != 0.0 {
+                self.translate(-rx, -ry);
+            }
+        }
+
+        let mut moves = Vec::new();
+        for pin in &mut self.pins {
+            let dx = -residual(pin.pos_x);
+            let dy = -residual(pin.pos_y);
+            if dx.abs() < 1e-9 && dy.abs() < 1e-9 {
+                continue;
+            }
+
+            // Keep the body end in place along the pin axis
+            let angle = ((180 + pin.rotation) % 360) as f64;
+            let (dir_x, dir_y) = (angle.to_radians().cos().round(), angle.to_radians().sin().round());
+            pin.length -= dx * dir_x + dy * dir_y;
+
+            pin.pos_x += dx;
+            pin.pos_y += dy;
+            moves.push(PinMove { number: pin.number.clone(), unit: pin.unit, dx, dy });
+        }
+
+        moves
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -134,6 +247,7 @@ pub struct KiPin {
     pub pos_y: f64,
     pub rotation: i32,
     pub length: f64,
+    pub unit: u32,
 }
 
 #[derive(Debug, Clone)]
@@ -144,6 +258,7 @@ pub struct KiRectangle {
     pub y2: f64,
     pub stroke_width: f64,
     pub fill: bool,
+    pub unit: u32,
 }
 
 #[derive(Debug, Clone)]
@@ -153,6 +268,7 @@ pub struct KiCircle {
     pub radius: f64,
     pub stroke_width: f64,
     pub fill: bool,
+    pub unit: u32,
 }
 
 #[derive(Debug, Clone)]
@@ -164,6 +280,7 @@ pub struct KiArc {
     pub end_x: f64,
     pub end_y: f64,
     pub stroke_width: f64,
+    pub unit: u32,
 }
 
 #[derive(Debug, Clone)]
@@ -171,4 +288,89 @@ pub struct KiPolyline {
     pub points: Vec<(f64, f64)>,
     pub stroke_width: f64,
     pub fill: bool,
+    pub unit: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct KiText {
+    pub text: String,
+    pub pos_x: f64,
+    pub pos_y: f64,
+    pub rotation: i32,
+    pub font_size: f64,
+    pub unit: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pin(number: &str, x: f64, y: f64, rotation: i32) -> KiPin {
+        KiPin {
+            number: number.to_string(),
+            name: number.to_string(),
+            pin_type: PinType::Unspecified,
+            style: PinStyle::Line,
+            pos_x: x,
+            pos_y: y,
+            rotation,
+            length: 10.0,
+            unit: 1,
+        }
+    }
+
+    fn symbol(pins: Vec<KiPin>) -> KiSymbol {
+        KiSymbol {
+            name: "TEST".to_string(),
+            reference: "U".to_string(),
+            value: "TEST".to_string(),
+            footprint: String::new(),
+            fp_filters: Vec::new(),
+            datasheet: String::new(),
+            manufacturer: String::new(),
+            lcsc_id: String::new(),
+            jlc_id: String::new(),
+            unit_count: 1,
+            pins,
+            rectangles: vec![KiRectangle { x1: -8.0, y1: 8.0, x2: 8.0, y2: -8.0, stroke_width: 0.0, fill: false, unit: 1 }],
+            circles: Vec::new(),
+            arcs: Vec::new(),
+            polylines: Vec::new(),
+            texts: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_pin_type_from_easyeda() {
+        assert_eq!(PinType::from_easyeda("0"), PinType::Unspecified);
+        assert_eq!(PinType::from_easyeda("1"), PinType::Input);
+        assert_eq!(PinType::from_easyeda("2"), PinType::Output);
+        assert_eq!(PinType::from_easyeda("3"), PinType::Bidirectional);
+        assert_eq!(PinType::from_easyeda("4"), PinType::PowerIn);
+        assert_eq!(PinType::from_easyeda("W"), PinType::PowerIn);
+        assert_eq!(PinType::from_easyeda(""), PinType::Unspecified);
+    }
+
+    #[test]
+    fn test_snap_shifts_consistent_offset() {
+        let mut sym = symbol(vec![pin("1", -18.0, 2.0, 180), pin("2", -18.0, -8.0, 180)]);
+        let moves = sym.snap_pins_to_grid(5.0);
+
+        assert!(moves.is_empty());
+        assert_eq!((sym.pins[0].pos_x, sym.pins[0].pos_y), (-20.0, 0.0));
+        assert_eq!(sym.pins[0].length, 10.0);
+        assert_eq!((sym.rectangles[0].x1, sym.rectangles[0].y1), (-10.0, 6.0));
+    }
+
+    #[test]
+    fn test_snap_single_pin_keeps_body_end() {
+        // Pin 2 sits 1 unit further out than the others; the body end is at x = -10
+        let mut sym = symbol(vec![pin("1", -20.0, 0.0, 180), pin("2", -21.0, -5.0, 180), pin("3", -20.0, 5.0, 180)]);
+        let moves = sym.snap_pins_to_grid(5.0);
+
+        assert_eq!(moves, vec![PinMove { number: "2".to_string(), unit: 1, dx: 1.0, dy: 0.0 }]);
+        assert_eq!(sym.pins[1].pos_x, -20.0);
+        assert_eq!(sym.pins[1].pos_x + sym.pins[1].length, -11.0);
+        assert_eq!(sym.rectangles[0].x1, -8.0);
+    }
 }
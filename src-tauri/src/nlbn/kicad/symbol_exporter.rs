@@ -1,6 +1,7 @@
 use super::super::KicadVersion;
 use super::super::converter::Converter;
 use super::super::error::Result;
+use super::sexpr::Num;
 use super::symbol::*;
 
 pub struct SymbolExporter {
@@ -17,12 +18,14 @@ impl SymbolExporter {
     }
 
     pub fn export(&self, symbol: &KiSymbol) -> Result<String> {
-        match self.version {
-            KicadVersion::V6 => self.export_v6(symbol),
-            KicadVersion::V5 => self.export_v5(symbol),
+        if self.version.is_legacy() {
+            self.export_v5(symbol)
+        } else {
+            self.export_v6(symbol)
         }
     }
 
+    /// Export for KiCad 6 and newer; `self.version` selects the dialect
     fn export_v6(&self, symbol: &KiSymbol) -> Result<String> {
         let mut output = String::new();
 
@@ -31,6 +34,9 @@ impl SymbolExporter {
 
         // Start symbol definition - match Python formatting
         output.push_str(&format!("  (symbol \"{}\"\n", symbol.name));
+        if self.version >= KicadVersion::V8 {
+            output.push_str("    (exclude_from_sim no)\n");
+        }
         output.push_str("    (in_bom yes)\n");
         output.push_str("    (on_board yes)\n");
 
@@ -38,136 +44,124 @@ impl SymbolExporter {
         const FIELD_OFFSET_START: f64 = 5.08;
         const FIELD_OFFSET_INCREMENT: f64 = 2.54;
         let mut field_offset_y = FIELD_OFFSET_START;
-        let mut property_id = 0;
-
-        // Reference property
-        output.push_str("    (property\n");
-        output.push_str("      \"Reference\"\n");
-        output.push_str(&format!("      \"{}\"\n", symbol.reference));
-        output.push_str(&format!("      (id {})\n", property_id));
-        output.push_str(&format!("      (at 0 {:.2} 0)\n", y_high + field_offset_y));
-        output.push_str("      (effects (font (size 1.27 1.27) ) )\n");
-        output.push_str("    )\n");
-        property_id += 1;
-
-        // Value property
-        output.push_str("    (property\n");
-        output.push_str("      \"Value\"\n");
-        output.push_str(&format!("      \"{}\"\n", symbol.value));
-        output.push_str(&format!("      (id {})\n", property_id));
-        output.push_str(&format!("      (at 0 {:.2} 0)\n", y_low - field_offset_y));
-        output.push_str("      (effects (font (size 1.27 1.27) ) )\n");
-        output.push_str("    )\n");
-        property_id += 1;
 
-        // Footprint property
-        if !symbol.footprint.is_empty() {
+        // Reference and value, both visible
+        output.push_str(&self.format_property_v6("Reference", &symbol.reference, 0, y_high + field_offset_y, false));
+        output.push_str(&self.format_property_v6("Value", &symbol.value, 1, y_low - field_offset_y, false));
+
+        // Hidden properties below the value, skipping empty ones
+        let fp_filters = symbol.fp_filters.join(" ");
+        let hidden = [
+            ("Footprint", symbol.footprint.as_str()),
+            ("Datasheet", symbol.datasheet.as_str()),
+            ("Manufacturer", symbol.manufacturer.as_str()),
+            ("LCSC Part", symbol.lcsc_id.as_str()),
+            ("JLC Part", symbol.jlc_id.as_str()),
+            ("ki_fp_filters", fp_filters.as_str()),
+        ];
+        let mut property_id = 2;
+        for (name, value) in hidden {
+            if value.is_empty() {
+                continue;
+            }
             field_offset_y += FIELD_OFFSET_INCREMENT;
-            output.push_str("    (property\n");
-            output.push_str("      \"Footprint\"\n");
-            output.push_str(&format!("      \"{}\"\n", symbol.footprint));
-            output.push_str(&format!("      (id {})\n", property_id));
-            output.push_str(&format!("      (at 0 {:.2} 0)\n", y_low - field_offset_y));
-            output.push_str("      (effects (font (size 1.27 1.27) ) hide)\n");
-            output.push_str("    )\n");
+            output.push_str(&self.format_property_v6(name, value, property_id, y_low - field_offset_y, true));
             property_id += 1;
         }
 
-        // Datasheet property
-        if !symbol.datasheet.is_empty() {
-            field_offset_y += FIELD_OFFSET_INCREMENT;
-            output.push_str("    (property\n");
-            output.push_str("      \"Datasheet\"\n");
-            output.push_str(&format!("      \"{}\"\n", symbol.datasheet));
-            output.push_str(&format!("      (id {})\n", property_id));
-            output.push_str(&format!("      (at 0 {:.2} 0)\n", y_low - field_offset_y));
-            output.push_str("      (effects (font (size 1.27 1.27) ) hide)\n");
-            output.push_str("    )\n");
-            property_id += 1;
+        if symbol.unit_count <= 1 {
+            // Symbol graphics section (unit 0, convert 1) - contains body graphics
+            output.push_str(&self.format_unit_v6(symbol, 0, |_| true));
+        } else {
+            // One section per unit: {name}_N_1
+            for unit in 1..=symbol.unit_count {
+                output.push_str(&self.format_unit_v6(symbol, unit, |item_unit| item_unit == unit));
+            }
         }
 
-        // Manufacturer property
-        if !symbol.manufacturer.is_empty() {
-            field_offset_y += FIELD_OFFSET_INCREMENT;
-            output.push_str("    (property\n");
-            output.push_str("      \"Manufacturer\"\n");
-            output.push_str(&format!("      \"{}\"\n", symbol.manufacturer));
-            output.push_str(&format!("      (id {})\n", property_id));
-            output.push_str(&format!("      (at 0 {:.2} 0)\n", y_low - field_offset_y));
-            output.push_str("      (effects (font (size 1.27 1.27) ) hide)\n");
-            output.push_str("    )\n");
-            property_id += 1;
+        if self.version >= KicadVersion::V9 {
+            output.push_str("    (embedded_fonts no)\n");
         }
 
-        // LCSC Part property
-        if !symbol.lcsc_id.is_empty() {
-            field_offset_y += FIELD_OFFSET_INCREMENT;
-            output.push_str("    (property\n");
-            output.push_str("      \"LCSC Part\"\n");
-            output.push_str(&format!("      \"{}\"\n", symbol.lcsc_id));
-            output.push_str(&format!("      (id {})\n", property_id));
-            output.push_str(&format!("      (at 0 {:.2} 0)\n", y_low - field_offset_y));
-            output.push_str("      (effects (font (size 1.27 1.27) ) hide)\n");
-            output.push_str("    )\n");
-            property_id += 1;
-        }
+        output.push_str("  )\n");
 
-        // JLC Part property
-        if !symbol.jlc_id.is_empty() {
-            field_offset_y += FIELD_OFFSET_INCREMENT;
-            output.push_str("    (property\n");
-            output.push_str("      \"JLC Part\"\n");
-            output.push_str(&format!("      \"{}\"\n", symbol.jlc_id));
-            output.push_str(&format!("      (id {})\n", property_id));
-            output.push_str(&format!("      (at 0 {:.2} 0)\n", y_low - field_offset_y));
-            output.push_str("      (effects (font (size 1.27 1.27) ) hide)\n");
-            output.push_str("    )\n");
+        Ok(output)
+    }
+
+    /// Format a symbol property; `id` is only written before KiCad 8
+    fn format_property_v6(&self, name: &str, value: &str, id: u32, y: f64, hidden: bool) -> String {
+        let mut output = format!("    (property\n      \"{}\"\n      \"{}\"\n", name, escape_text(value));
+        if self.version < KicadVersion::V8 {
+            output.push_str(&format!("      (id {})\n", id));
         }
+        output.push_str(&format!("      (at 0 {} 0)\n", Num(y)));
+
+        // KiCad 9 moved the hide flag out of the effects
+        match (hidden, self.version) {
+            (false, _) => output.push_str("      (effects (font (size 1.27 1.27) ) )\n"),
+            (true, KicadVersion::V9) => {
+                output.push_str("      (hide yes)\n");
+                output.push_str("      (effects (font (size 1.27 1.27) ) )\n");
+            }
+            (true, KicadVersion::V8) => output.push_str("      (effects (font (size 1.27 1.27) ) (hide yes))\n"),
+            (true, _) => output.push_str("      (effects (font (size 1.27 1.27) ) hide)\n"),
+        }
+
+        output.push_str("    )\n");
+        output
+    }
 
-        // Symbol graphics section (unit 0, convert 1) - contains body graphics
-        output.push_str(&format!("    (symbol \"{}_0_1\"\n", symbol.name));
+    /// Format a `(symbol "{name}_{unit}_1" ...)` section with the items accepted by `in_unit`
+    fn format_unit_v6(&self, symbol: &KiSymbol, unit: u32, in_unit: impl Fn(u32) -> bool) -> String {
+        let mut output = format!("    (symbol \"{}_{}_1\"\n", symbol.name, unit);
 
         // Rectangles
-        for rect in &symbol.rectangles {
+        for rect in symbol.rectangles.iter().filter(|r| in_unit(r.unit)) {
             output.push_str(&self.format_rectangle_v6(rect));
         }
 
         // Circles
-        for circle in &symbol.circles {
+        for circle in symbol.circles.iter().filter(|c| in_unit(c.unit)) {
             output.push_str(&self.format_circle_v6(circle));
         }
 
         // Arcs
-        for arc in &symbol.arcs {
+        for arc in symbol.arcs.iter().filter(|a| in_unit(a.unit)) {
             output.push_str(&self.format_arc_v6(arc));
         }
 
         // Polylines
-        for polyline in &symbol.polylines {
+        for polyline in symbol.polylines.iter().filter(|p| in_unit(p.unit)) {
             output.push_str(&self.format_polyline_v6(polyline));
         }
 
-        // Pins - in the same _0_1 section as graphics
-        for pin in &symbol.pins {
+        // Texts
+        for text in symbol.texts.iter().filter(|t| in_unit(t.unit)) {
+            output.push_str(&self.format_text_v6(text));
+        }
+
+        // Pins - in the same section as graphics
+        for pin in symbol.pins.iter().filter(|p| in_unit(p.unit)) {
             output.push_str(&self.format_pin_v6(pin));
         }
 
         output.push_str("    )\n");
-        output.push_str("  )\n");
-
-        Ok(output)
+        output
     }
 
+    /// Vertical extent of the pins and body graphics, so fields don't overlap the body
     fn calculate_y_bounds(&self, symbol: &KiSymbol) -> (f64, f64) {
-        if symbol.pins.is_empty() {
-            return (0.0, 0.0);
-        }
+        let ys = symbol.pins.iter().map(|pin| pin.pos_y)
+            .chain(symbol.rectangles.iter().flat_map(|rect| [rect.y1, rect.y2]))
+            .chain(symbol.circles.iter().flat_map(|circle| [circle.cy - circle.radius, circle.cy + circle.radius]))
+            .chain(symbol.arcs.iter().flat_map(|arc| [arc.start_y, arc.mid_y, arc.end_y]))
+            .chain(symbol.polylines.iter().flat_map(|polyline| polyline.points.iter().map(|(_, y)| *y)));
 
         let mut y_high = f64::MIN;
         let mut y_low = f64::MAX;
 
-        for pin in &symbol.pins {
-            let y = self.converter.px_to_mm(pin.pos_y);
+        for y in ys {
+            let y = self.converter.px_to_mm(y);
             if y > y_high {
                 y_high = y;
             }
@@ -176,6 +170,10 @@ impl SymbolExporter {
             }
         }
 
+        if y_high < y_low {
+            return (0.0, 0.0);
+        }
+
         (y_high, y_low)
     }
 
@@ -184,8 +182,8 @@ impl SymbolExporter {
 
         // DEF name reference unused text_offset draw_pinnumber draw_pinname unit_count units_locked option_flag
         output.push_str(&format!(
-            "DEF {} {} 0 40 Y Y 1 F N\n",
-            symbol.name, symbol.reference
+            "DEF {} {} 0 40 Y Y {} F N\n",
+            symbol.name, symbol.reference, symbol.unit_count.max(1)
         ));
 
         // F0 reference x y size orientation visibility hjustify vjustify/italic/bold
@@ -194,6 +192,15 @@ impl SymbolExporter {
         output.push_str(&format!("F2 \"{}\" 0 0 50 H I C CNN\n", symbol.footprint));
         output.push_str(&format!("F3 \"{}\" 0 0 50 H I C CNN\n", symbol.datasheet));
 
+        // Footprint filters
+        if !symbol.fp_filters.is_empty() {
+            output.push_str("$FPLIST\n");
+            for filter in &symbol.fp_filters {
+                output.push_str(&format!(" {}\n", filter));
+            }
+            output.push_str("$ENDFPLIST\n");
+        }
+
         // DRAW
         output.push_str("DRAW\n");
 
@@ -207,11 +214,21 @@ impl SymbolExporter {
             output.push_str(&self.format_circle_v5(circle));
         }
 
+        // Arcs
+        for arc in &symbol.arcs {
+            output.push_str(&self.format_arc_v5(arc));
+        }
+
         // Polylines
         for polyline in &symbol.polylines {
             output.push_str(&self.format_polyline_v5(polyline));
         }
 
+        // Texts
+        for text in &symbol.texts {
+            output.push_str(&self.format_text_v5(text));
+        }
+
         // Pins
         for pin in &symbol.pins {
             output.push_str(&self.format_pin_v5(pin));
@@ -232,15 +249,15 @@ impl SymbolExporter {
         let orientation = (180 + pin.rotation) % 360;
 
         format!(
-            "      (pin {} {}\n        (at {:.2} {:.2} {})\n        (length {:.2})\n        (name \"{}\" (effects (font (size 1.27 1.27))))\n        (number \"{}\" (effects (font (size 1.27 1.27))))\n      )\n",
+            "      (pin {} {}\n        (at {} {} {})\n        (length {})\n        (name \"{}\" (effects (font (size 1.27 1.27))))\n        (number \"{}\" (effects (font (size 1.27 1.27))))\n      )\n",
             pin.pin_type.to_kicad_v6(),
             pin.style.to_kicad_v6(),
-            x,
-            y,
+            Num(x),
+            Num(y),
             orientation,
-            length,
-            pin.name,
-            pin.number
+            Num(length),
+            escape_text(&pin.name),
+            escape_text(&pin.number)
         )
     }
 
@@ -249,6 +266,9 @@ impl SymbolExporter {
         let y = self.converter.px_to_mil(pin.pos_y);  // Don't flip, already handled
         let length = self.converter.px_to_mil(pin.length);
 
+        // Same direction as the V6 orientation: from the pin end towards the body
+        let orientation = (180 + pin.rotation) % 360;
+
         // X name number posx posy length orientation Snum Snom unit convert Etype [shape]
         format!(
             "X {} {} {} {} {} {} {} {} {} {} {}\n",
@@ -257,10 +277,10 @@ impl SymbolExporter {
             x,
             y,
             length,
-            self.rotation_to_direction(pin.rotation),
+            self.rotation_to_direction(orientation),
             50, // name size
             50, // number size
-            1,  // unit
+            pin.unit,
             1,  // convert
             pin.pin_type.to_kicad_v5()
         )
@@ -276,8 +296,8 @@ impl SymbolExporter {
         let fill = if rect.fill { "background" } else { "none" };
 
         format!(
-            "      (rectangle\n        (start {:.2} {:.2})\n        (end {:.2} {:.2})\n        (stroke (width {}) (type default) (color 0 0 0 0))\n        (fill (type {}))\n      )\n",
-            x1, y1, x2, y2, 0, fill
+            "      (rectangle\n        (start {} {})\n        (end {} {})\n        (stroke (width {}) (type default) (color 0 0 0 0))\n        (fill (type {}))\n      )\n",
+            Num(x1), Num(y1), Num(x2), Num(y2), 0, fill
         )
     }
 
@@ -290,7 +310,7 @@ impl SymbolExporter {
         let fill = if rect.fill { "F" } else { "N" };
 
         // S startx starty endx endy unit convert thickness fill
-        format!("S {} {} {} {} 1 1 10 {}\n", x1, y1, x2, y2, fill)
+        format!("S {} {} {} {} {} 1 10 {}\n", x1, y1, x2, y2, rect.unit, fill)
     }
 
     fn format_circle_v6(&self, circle: &KiCircle) -> String {
@@ -302,8 +322,8 @@ impl SymbolExporter {
         let fill = "none";
 
         format!(
-            "      (circle\n        (center {:.2} {:.2})\n        (radius {:.2})\n        (stroke (width {}) (type default) (color 0 0 0 0))\n        (fill (type {}))\n      )\n",
-            cx, cy, radius, 0, fill
+            "      (circle\n        (center {} {})\n        (radius {})\n        (stroke (width {}) (type default) (color 0 0 0 0))\n        (fill (type {}))\n      )\n",
+            Num(cx), Num(cy), Num(radius), 0, fill
         )
     }
 
@@ -315,7 +335,7 @@ impl SymbolExporter {
         let fill = if circle.fill { "F" } else { "N" };
 
         // C posx posy radius unit convert thickness fill
-        format!("C {} {} {} 1 1 10 {}\n", cx, cy, radius, fill)
+        format!("C {} {} {} {} 1 10 {}\n", cx, cy, radius, circle.unit, fill)
     }
 
     fn format_arc_v6(&self, arc: &KiArc) -> String {
@@ -328,8 +348,51 @@ impl SymbolExporter {
         let width = self.converter.px_to_mm(arc.stroke_width);
 
         format!(
-            "    (arc (start {:.4} {:.4}) (mid {:.4} {:.4}) (end {:.4} {:.4})\n      (stroke (width {:.4}) (type default))\n      (fill (type none))\n    )\n",
-            start_x, start_y, mid_x, mid_y, end_x, end_y, width
+            "    (arc (start {} {}) (mid {} {}) (end {} {})\n      (stroke (width {}) (type default))\n      (fill (type none))\n    )\n",
+            Num(start_x), Num(start_y), Num(mid_x), Num(mid_y), Num(end_x), Num(end_y), Num(width)
+        )
+    }
+
+    fn format_arc_v5(&self, arc: &KiArc) -> String {
+        let mut start = (arc.start_x, arc.start_y);
+        let mut end = (arc.end_x, arc.end_y);
+        let mid = (arc.mid_x, arc.mid_y);
+
+        let Some((cx, cy, radius)) = self.converter.arc_center_from_points(start, mid, end) else {
+            // Degenerate arc: draw it as a straight segment
+            return self.format_polyline_v5(&KiPolyline {
+                points: vec![start, end],
+                stroke_width: arc.stroke_width,
+                fill: false,
+                unit: arc.unit,
+            });
+        };
+
+        // v5 arcs run counterclockwise from the start angle to the end angle
+        let cross = (mid.0 - start.0) * (end.1 - mid.1) - (mid.1 - start.1) * (end.0 - mid.0);
+        if cross < 0.0 {
+            std::mem::swap(&mut start, &mut end);
+        }
+
+        let start_angle = (self.converter.rad_to_deg((start.1 - cy).atan2(start.0 - cx)) * 10.0).round() as i32;
+        let mut end_angle = (self.converter.rad_to_deg((end.1 - cy).atan2(end.0 - cx)) * 10.0).round() as i32;
+        if end_angle <= start_angle {
+            end_angle += 3600;
+        }
+
+        // A posx posy radius start_angle end_angle unit convert thickness fill startx starty endx endy
+        format!(
+            "A {} {} {} {} {} {} 1 10 N {} {} {} {}\n",
+            self.converter.px_to_mil(cx),
+            self.converter.px_to_mil(cy),
+            self.converter.px_to_mil(radius),
+            start_angle,
+            end_angle,
+            arc.unit,
+            self.converter.px_to_mil(start.0),
+            self.converter.px_to_mil(start.1),
+            self.converter.px_to_mil(end.0),
+            self.converter.px_to_mil(end.1)
         )
     }
 
@@ -339,14 +402,14 @@ impl SymbolExporter {
         for (x, y) in &polyline.points {
             let x = self.converter.px_to_mm(*x);
             let y = self.converter.px_to_mm(*y);  // Don't flip, already handled
-            output.push_str(&format!("        (xy {:.4} {:.4})\n", x, y));
+            output.push_str(&format!("        (xy {} {})\n", Num(x), Num(y)));
         }
 
         let width = self.converter.px_to_mm(polyline.stroke_width);
         let fill = if polyline.fill { "background" } else { "none" };
 
         output.push_str("      )\n");
-        output.push_str(&format!("      (stroke (width {:.4}) (type default))\n", width));
+        output.push_str(&format!("      (stroke (width {}) (type default))\n", Num(width)));
         output.push_str(&format!("      (fill (type {}))\n", fill));
         output.push_str("    )\n");
 
@@ -355,7 +418,7 @@ impl SymbolExporter {
 
     fn format_polyline_v5(&self, polyline: &KiPolyline) -> String {
         let point_count = polyline.points.len();
-        let mut output = format!("P {} 1 1 10", point_count);
+        let mut output = format!("P {} {} 1 10", point_count, polyline.unit);
 
         for (x, y) in &polyline.points {
             let x = self.converter.px_to_mil(*x);
@@ -369,6 +432,40 @@ impl SymbolExporter {
         output
     }
 
+    fn format_text_v6(&self, text: &KiText) -> String {
+        let x = self.converter.px_to_mm(text.pos_x);
+        let y = self.converter.px_to_mm(text.pos_y);  // Don't flip, already handled
+        let size = self.converter.px_to_mm(text.font_size);
+
+        // Symbol text angles are stored in tenths of a degree
+        format!(
+            "      (text \"{}\" (at {} {} {})\n        (effects (font (size {} {})) (justify left bottom))\n      )\n",
+            escape_text(&text.text),
+            Num(x),
+            Num(y),
+            text.rotation * 10,
+            Num(size),
+            Num(size)
+        )
+    }
+
+    fn format_text_v5(&self, text: &KiText) -> String {
+        let x = self.converter.px_to_mil(text.pos_x);
+        let y = self.converter.px_to_mil(text.pos_y);  // Don't flip, already handled
+        let size = self.converter.px_to_mil(text.font_size);
+
+        // T angle posx posy size hidden unit convert text italic bold hjustify vjustify
+        format!(
+            "T {} {} {} {} 0 {} 1 \"{}\" Normal 0 L B\n",
+            text.rotation * 10,
+            x,
+            y,
+            size,
+            text.unit,
+            escape_text(&text.text)
+        )
+    }
+
     fn rotation_to_direction(&self, rotation: i32) -> char {
         match rotation {
             0 => 'R',
@@ -379,3 +476,80 @@ impl SymbolExporter {
         }
     }
 }
+
+/// Escape a string for use inside a quoted symbol field
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::sexpr::SExpr;
+
+    fn symbol() -> KiSymbol {
+        KiSymbol {
+            name: "TEST".to_string(),
+            reference: "U".to_string(),
+            value: "TEST".to_string(),
+            footprint: String::new(),
+            fp_filters: Vec::new(),
+            datasheet: String::new(),
+            manufacturer: String::new(),
+            lcsc_id: String::new(),
+            jlc_id: String::new(),
+            unit_count: 1,
+            pins: Vec::new(),
+            rectangles: Vec::new(),
+            circles: Vec::new(),
+            arcs: Vec::new(),
+            polylines: Vec::new(),
+            texts: Vec::new(),
+        }
+    }
+
+    /// Text of the property `name` in exported symbol `output`
+    fn property(output: &str, name: &str) -> Option<String> {
+        let root = SExpr::parse(output).unwrap();
+        root.items()?.iter()
+            .filter(|item| item.head() == Some("property"))
+            .find(|item| item.items().and_then(|p| p.get(1)).and_then(SExpr::as_str) == Some(name))
+            .and_then(|item| item.items()?.get(2)?.as_str().map(str::to_string))
+    }
+
+    #[test]
+    fn test_property_values_are_escaped() {
+        let mut sym = symbol();
+        sym.value = "1/4\" 10\\20".to_string();
+        sym.manufacturer = "\"ACME\" Corp".to_string();
+
+        for version in [KicadVersion::V6, KicadVersion::V9] {
+            let output = SymbolExporter::new(version).export(&sym).unwrap();
+            assert_eq!(property(&output, "Value").as_deref(), Some("1/4\" 10\\20"));
+            assert_eq!(property(&output, "Manufacturer").as_deref(), Some("\"ACME\" Corp"));
+        }
+    }
+
+    #[test]
+    fn test_pin_names_are_escaped() {
+        let mut sym = symbol();
+        sym.pins.push(KiPin {
+            number: "A\"1".to_string(),
+            name: "~{RST} \"N\"".to_string(),
+            pin_type: PinType::Input,
+            style: PinStyle::Line,
+            pos_x: -20.0,
+            pos_y: 0.0,
+            rotation: 0,
+            length: 10.0,
+            unit: 1,
+        });
+
+        let output = SymbolExporter::new(KicadVersion::V6).export(&sym).unwrap();
+        let root = SExpr::parse(&output).unwrap();
+        let pin = root.find("symbol").and_then(|unit| unit.find("pin")).unwrap();
+        let field = |key: &str| pin.find(key).and_then(|f| f.items()?.get(1)?.as_str().map(str::to_string));
+        assert_eq!(field("name").as_deref(), Some("~{RST} \"N\""));
+        assert_eq!(field("number").as_deref(), Some("A\"1"));
+    }
+}
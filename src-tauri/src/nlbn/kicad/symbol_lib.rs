@@ -0,0 +1,181 @@
+//! Structural editing of `.kicad_sym` symbol libraries
+
+use super::super::error::{KicadError, Result};
+use super::super::KicadVersion;
+use super::sexpr::SExpr;
+
+/// A parsed `(kicad_symbol_lib ...)` file
+#[derive(Debug, Clone)]
+pub struct SymbolLibrary {
+    root: SExpr,
+}
+
+impl SymbolLibrary {
+    /// Create an empty library in the format of the given KiCad version
+    pub fn new(version: KicadVersion) -> Self {
+        let mut header = vec![SExpr::list("version", vec![SExpr::atom(version.symbol_lib_version())])];
+        if version >= KicadVersion::V8 {
+            header.push(SExpr::list("generator", vec![SExpr::string("nlbn")]));
+            header.push(SExpr::list("generator_version", vec![SExpr::string(env!("CARGO_PKG_VERSION"))]));
+        } else {
+            header.push(SExpr::list("generator", vec![SExpr::atom("nlbn")]));
+        }
+
+        Self {
+            root: SExpr::list("kicad_symbol_lib", header),
+        }
+    }
+
+    /// Parse the content of a `.kicad_sym` file
+    pub fn parse(content: &str) -> Result<Self> {
+        let root = SExpr::parse(content)?;
+        if root.head() != Some("kicad_symbol_lib") {
+            return Err(KicadError::Parse("not a kicad_symbol_lib file".to_string()).into());
+        }
+
+        Ok(Self { root })
+    }
+
+    /// Raise the file format version to `version` if the file is older, so
+    /// symbols written in a newer format are read with the right parser
+    pub fn upgrade_version(&mut self, version: KicadVersion) {
+        let target = version.symbol_lib_version();
+        let items = self.root.items_mut().expect("library root is a list");
+
+        match items.iter_mut().find(|item| item.head() == Some("version")) {
+            Some(item) => {
+                let current = item.items().and_then(|v| v.get(1)).and_then(SExpr::as_str).unwrap_or_default();
+                if current < target {
+                    *item = SExpr::list("version", vec![SExpr::atom(target)]);
+                }
+            }
+            None => items.insert(1, SExpr::list("version", vec![SExpr::atom(target)])),
+        }
+    }
+
+    fn symbols(&self) -> impl Iterator<Item = &SExpr> {
+        self.root.items().unwrap_or_default().iter().filter(|item| item.head() == Some("symbol"))
+    }
+
+    /// Names of all top-level symbols
+    pub fn symbol_names(&self) -> Vec<String> {
+        self.symbols().filter_map(symbol_name).map(str::to_string).collect()
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.symbols().any(|symbol| symbol_name(symbol) == Some(name))
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        self.root.items()?
+            .iter()
+            .position(|item| item.head() == Some("symbol") && symbol_name(item) == Some(name))
+    }
+
+    /// Insert a symbol, replacing an existing symbol with the same name in place
+    ///
+    /// `symbol_text` is a single `(symbol "name" ...)` expression. Returns true
+    /// if an existing symbol was replaced.
+    pub fn upsert_symbol(&mut self, symbol_text: &str) -> Result<bool> {
+        let symbol = SExpr::parse(symbol_text)?;
+        let name = match (symbol.head(), symbol_name(&symbol)) {
+            (Some("symbol"), Some(name)) => name.to_string(),
+            _ => return Err(KicadError::SymbolExport("expected a (symbol \"name\" ...) expression".to_string()).into()),
+        };
+
+        let position = self.position(&name);
+        let items = self.root.items_mut().expect("library root is a list");
+
+        match position {
+            Some(index) => {
+                items[index] = symbol;
+                Ok(true)
+            }
+            None => {
+                items.push(symbol);
+                Ok(false)
+            }
+        }
+    }
+
+    /// Remove a symbol by name; returns true if it was present
+    pub fn remove_symbol(&mut self, name: &str) -> bool {
+        match self.position(name) {
+            Some(index) => {
+                self.root.items_mut().expect("library root is a list").remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Serialize in KiCad's canonical layout
+    pub fn to_string_pretty(&self) -> String {
+        self.root.to_pretty_string()
+    }
+}
+
+impl Default for SymbolLibrary {
+    fn default() -> Self {
+        Self::new(KicadVersion::V6)
+    }
+}
+
+fn symbol_name(symbol: &SExpr) -> Option<&str> {
+    symbol.items()?.get(1)?.as_str()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RESISTOR: &str = "(symbol \"R\" (in_bom yes) (property \"Reference\" \"R\" (id 0) (at 0 0 0)))";
+
+    #[test]
+    fn test_upsert_and_remove() {
+        let mut lib = SymbolLibrary::new(KicadVersion::V6);
+        assert!(!lib.upsert_symbol(RESISTOR).unwrap());
+        assert!(!lib.upsert_symbol("(symbol \"C\" (in_bom yes))").unwrap());
+        assert!(lib.upsert_symbol("(symbol \"R\" (in_bom no))").unwrap());
+
+        // Replaced in place, order preserved
+        assert_eq!(lib.symbol_names(), vec!["R", "C"]);
+        let reparsed = SymbolLibrary::parse(&lib.to_string_pretty()).unwrap();
+        assert!(reparsed.to_string_pretty().contains("(in_bom no)"));
+
+        assert!(lib.remove_symbol("R"));
+        assert!(!lib.remove_symbol("R"));
+        assert!(!lib.contains("R"));
+        assert!(lib.contains("C"));
+    }
+
+    #[test]
+    fn test_names_with_special_characters() {
+        let mut lib = SymbolLibrary::new(KicadVersion::V6);
+        lib.upsert_symbol("(symbol \"A (\\\"B\\\")\" (in_bom yes))").unwrap();
+
+        let reparsed = SymbolLibrary::parse(&lib.to_string_pretty()).unwrap();
+        assert!(reparsed.contains("A (\"B\")"));
+    }
+
+    #[test]
+    fn test_version_header() {
+        let lib = SymbolLibrary::new(KicadVersion::V9).to_string_pretty();
+        assert!(lib.contains("(version 20241209)"));
+        assert!(lib.contains("(generator \"nlbn\")"));
+        assert!(lib.contains("(generator_version"));
+
+        // Older files are upgraded, newer ones left alone
+        let mut lib = SymbolLibrary::new(KicadVersion::V6);
+        lib.upgrade_version(KicadVersion::V8);
+        assert!(lib.to_string_pretty().contains("(version 20231120)"));
+        lib.upgrade_version(KicadVersion::V7);
+        assert!(lib.to_string_pretty().contains("(version 20231120)"));
+    }
+
+    #[test]
+    fn test_rejects_other_files() {
+        assert!(SymbolLibrary::parse("(footprint \"X\")").is_err());
+        assert!(SymbolLibrary::parse("(kicad_symbol_lib (version 20211014)").is_err());
+    }
+}
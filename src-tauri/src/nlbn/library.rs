@@ -1,35 +1,111 @@
 use super::error::{KicadError, Result};
+use super::kicad::{LibTable, LibTableKind, SExpr, SymbolLibrary};
+use super::KicadVersion;
 use regex::Regex;
 use std::fs;
+use std::io::Write;
 use std::path::{Path, PathBuf};
-use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
 
+/// Serializes symbol library updates within this process; `lock_library`
+/// does the same across processes. Symbol library updates block (up to the
+/// lock timeout), so async callers should run them on a blocking thread.
 static SYMBOL_WRITE_LOCK: Mutex<()> = Mutex::new(());
+static LIB_TABLE_LOCK: Mutex<()> = Mutex::new(());
+static TEMP_FILE_COUNTER: AtomicUsize = AtomicUsize::new(0);
 
+/// How long to wait for another process to finish updating a symbol library,
+/// unless configured with `with_lock_timeout`
+pub const DEFAULT_LOCK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Interval between attempts to take a held library lock
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Library nickname used when none is configured
+pub const DEFAULT_LIBRARY_NAME: &str = "nlbn";
+
+#[derive(Debug, Clone)]
 pub struct LibraryManager {
     output_path: PathBuf,
+    kicad_version: KicadVersion,
+    library_name: String,
+    keep_backups: bool,
+    lock_timeout: Duration,
 }
 
 impl LibraryManager {
     pub fn new(output_path: &Path) -> Self {
         Self {
             output_path: output_path.to_path_buf(),
+            kicad_version: KicadVersion::V6,
+            library_name: DEFAULT_LIBRARY_NAME.to_string(),
+            keep_backups: false,
+            lock_timeout: DEFAULT_LOCK_TIMEOUT,
         }
     }
 
+    /// Library nickname; names the symbol library, `.pretty` and `.3dshapes`
+    /// directories and prefixes footprint references
+    pub fn with_library_name(mut self, name: &str) -> Self {
+        self.library_name = name.to_string();
+        self
+    }
+
+    /// Keep overwritten footprints and 3D models as `<file>.<timestamp>.bak`
+    pub fn with_backups(mut self, keep_backups: bool) -> Self {
+        self.keep_backups = keep_backups;
+        self
+    }
+
+    /// How long symbol library updates wait for another process's lock
+    pub fn with_lock_timeout(mut self, timeout: Duration) -> Self {
+        self.lock_timeout = timeout;
+        self
+    }
+
+    pub fn library_name(&self) -> &str {
+        &self.library_name
+    }
+
+    fn pretty_dir(&self) -> PathBuf {
+        self.output_path.join(format!("{}.pretty", self.library_name))
+    }
+
+    /// Path of a file in the `.3dshapes` directory
+    pub fn model_path(&self, model_file: &str) -> PathBuf {
+        self.shapes_dir().join(model_file)
+    }
+
+    fn shapes_dir(&self) -> PathBuf {
+        self.output_path.join(format!("{}.3dshapes", self.library_name))
+    }
+
+    /// Format used for new `.kicad_sym` libraries; older existing libraries
+    /// are upgraded to it when a symbol is written
+    pub fn with_kicad_version(mut self, version: KicadVersion) -> Self {
+        self.kicad_version = version;
+        self
+    }
+
     /// Create necessary output directories
     pub fn create_directories(&self) -> Result<()> {
+        if !is_valid_library_name(&self.library_name) {
+            return Err(KicadError::InvalidLibraryName(self.library_name.clone()).into());
+        }
+
         // Create main output directory
         fs::create_dir_all(&self.output_path)
             .map_err(KicadError::Io)?;
 
         // Create .pretty directory for footprints
-        let pretty_dir = self.output_path.join("nlbn.pretty");
+        let pretty_dir = self.pretty_dir();
         fs::create_dir_all(&pretty_dir)
             .map_err(KicadError::Io)?;
 
         // Create .3dshapes directory for 3D models
-        let shapes_dir = self.output_path.join("nlbn.3dshapes");
+        let shapes_dir = self.shapes_dir();
         fs::create_dir_all(&shapes_dir)
             .map_err(KicadError::Io)?;
 
@@ -46,273 +122,677 @@ impl LibraryManager {
         let content = fs::read_to_string(lib_path)
             .map_err(KicadError::Io)?;
 
-        // Check for v6 format
-        let v6_pattern = format!(r#"\(symbol\s+"{}""#, regex::escape(component_name));
-        if let Ok(re) = Regex::new(&v6_pattern) {
-            if re.is_match(&content) {
-                return Ok(true);
-            }
-        }
-
-        // Check for v5 format
-        let v5_pattern = format!(r"DEF\s+{}\s+", regex::escape(component_name));
-        if let Ok(re) = Regex::new(&v5_pattern) {
-            if re.is_match(&content) {
-                return Ok(true);
-            }
+        if is_v5_library(lib_path) {
+            let v5_pattern = format!(r"(?m)^DEF\s+{}\s+", regex::escape(component_name));
+            return Ok(Regex::new(&v5_pattern)?.is_match(&content));
         }
 
-        Ok(false)
+        Ok(SymbolLibrary::parse(&content)?.contains(component_name))
     }
 
     /// Add or update a component in the library file (thread-safe)
     /// Returns true if the component was written, false if skipped (already exists and overwrite=false)
     pub fn add_or_update_component(&self, lib_path: &Path, component_name: &str, component_data: &str, overwrite: bool) -> Result<bool> {
         // Lock to prevent concurrent writes and check-then-act race conditions
-        let _lock = SYMBOL_WRITE_LOCK.lock().unwrap();
+        let _lock = self.lock_symbol_library(lib_path)?;
 
         // Check if component exists (within lock to prevent TOCTOU)
-        let exists = if lib_path.exists() {
-            let content = fs::read_to_string(lib_path)
-                .map_err(KicadError::Io)?;
+        if !overwrite && self.component_exists(lib_path, component_name)? {
+            log::info!("Component {} already exists, skipping (overwrite=false)", component_name);
+            return Ok(false);
+        }
+
+        self.upsert_component_internal(lib_path, component_name, component_data)?;
+        Ok(true)
+    }
+
+    /// Lock a symbol library against other threads and other processes; both
+    /// are released when the returned guards are dropped
+    fn lock_symbol_library(&self, lib_path: &Path) -> Result<(MutexGuard<'static, ()>, fs::File)> {
+        // Wait for other processes before taking the in-process lock, so a
+        // contended library does not hold up other threads for the timeout
+        let file_lock = self.lock_library(lib_path)?;
+        let lock = SYMBOL_WRITE_LOCK.lock().unwrap();
+        Ok((lock, file_lock))
+    }
 
-            let v6_pattern = format!(r#"\(symbol\s+"{}""#, regex::escape(component_name));
-            if let Ok(re) = Regex::new(&v6_pattern) {
-                re.is_match(&content)
-            } else {
-                false
+    /// Take the advisory `<library>.lock` file lock next to `lib_path`, waiting
+    /// up to the lock timeout for other processes to release it
+    ///
+    /// The lock is released when the returned file is dropped. The lock file
+    /// itself is left in place; removing it could race with another process.
+    fn lock_library(&self, lib_path: &Path) -> Result<fs::File> {
+        let lock_path = lib_path.with_file_name(format!(
+            "{}.lock",
+            lib_path.file_name().unwrap_or_default().to_string_lossy()
+        ));
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&lock_path)
+            .map_err(KicadError::Io)?;
+
+        let start = Instant::now();
+        loop {
+            match file.try_lock() {
+                Ok(()) => return Ok(file),
+                Err(fs::TryLockError::WouldBlock) if start.elapsed() < self.lock_timeout => {
+                    std::thread::sleep(LOCK_RETRY_INTERVAL);
+                }
+                Err(fs::TryLockError::WouldBlock) => {
+                    return Err(KicadError::Locked(
+                        lib_path.display().to_string(),
+                        self.lock_timeout.as_secs_f64(),
+                    ).into());
+                }
+                Err(fs::TryLockError::Error(e)) => return Err(KicadError::Io(e).into()),
             }
+        }
+    }
+
+    /// Internal insert-or-replace (assumes lock is held)
+    fn upsert_component_internal(&self, lib_path: &Path, component_name: &str, component_data: &str) -> Result<()> {
+        let existing = if lib_path.exists() {
+            Some(fs::read_to_string(lib_path).map_err(KicadError::Io)?)
         } else {
-            false
+            None
         };
 
-        if exists && overwrite {
-            // Update existing component
-            self.update_component_internal(lib_path, component_name, component_data)?;
-            Ok(true)
-        } else if !exists {
-            // Add new component
-            self.add_component_internal(lib_path, component_data)?;
-            Ok(true)
+        let content = if is_v5_library(lib_path) {
+            upsert_v5_component(existing.as_deref(), component_name, component_data)?
         } else {
-            // exists and !overwrite - skip
-            log::info!("Component {} already exists, skipping (overwrite=false)", component_name);
-            Ok(false)
-        }
+            let mut library = match &existing {
+                Some(content) => SymbolLibrary::parse(content)?,
+                None => SymbolLibrary::new(self.kicad_version),
+            };
+            library.upgrade_version(self.kicad_version);
+            library.upsert_symbol(component_data)?;
+            library.to_string_pretty()
+        };
+
+        validate_symbol_library(lib_path, &content)?;
+        write_atomic(lib_path, content.as_bytes())
     }
 
-    /// Internal add component (assumes lock is held)
-    fn add_component_internal(&self, lib_path: &Path, component_data: &str) -> Result<()> {
-        let mut content = if lib_path.exists() {
-            let existing = fs::read_to_string(lib_path)
-                .map_err(KicadError::Io)?;
-            existing.trim_end().trim_end_matches(')').to_string()
-        } else {
-            if component_data.contains("(symbol") {
-                String::from("(kicad_symbol_lib\n  (version 20211014)\n  (generator nlbn)")
-            } else {
-                String::from("EESchema-LIBRARY Version 2.4\n#encoding utf-8")
-            }
-        };
+    /// Add a component to the library file
+    pub fn add_component(&self, lib_path: &Path, component_data: &str) -> Result<()> {
+        // Lock to prevent concurrent writes to the same symbol library file
+        let _lock = self.lock_symbol_library(lib_path)?;
 
-        content.push('\n');
-        content.push_str(component_data);
+        // v6 symbols carry their own name; v5 blocks start with `DEF <name> ...`
+        let component_name = component_data
+            .lines()
+            .find_map(|line| line.strip_prefix("DEF "))
+            .and_then(|rest| rest.split_whitespace().next())
+            .unwrap_or_default();
 
-        if component_data.contains("(symbol") {
-            content.push('\n');
-            content.push(')');
-        }
-        content.push('\n');
+        self.upsert_component_internal(lib_path, component_name, component_data)
+    }
 
-        fs::write(lib_path, content)
-            .map_err(KicadError::Io)?;
+    /// Update an existing component in the library file
+    pub fn update_component(&self, lib_path: &Path, component_name: &str, new_data: &str) -> Result<()> {
+        // Lock to prevent concurrent writes to the same symbol library file
+        let _lock = self.lock_symbol_library(lib_path)?;
 
-        Ok(())
+        if !self.component_exists(lib_path, component_name)? {
+            return Err(KicadError::SymbolExport(format!("Component {} not found in library", component_name)).into());
+        }
+
+        self.upsert_component_internal(lib_path, component_name, new_data)
     }
 
-    /// Internal update component (assumes lock is held)
-    fn update_component_internal(&self, lib_path: &Path, component_name: &str, new_data: &str) -> Result<()> {
+    /// Remove a component from the library file; returns false if it was not present
+    pub fn remove_component(&self, lib_path: &Path, component_name: &str) -> Result<bool> {
+        let _lock = self.lock_symbol_library(lib_path)?;
+
+        if !lib_path.exists() || is_v5_library(lib_path) {
+            return Ok(false);
+        }
+
         let content = fs::read_to_string(lib_path)
             .map_err(KicadError::Io)?;
+        let mut library = SymbolLibrary::parse(&content)?;
 
-        // For KiCad v6 format: use (?s) flag to make . match newlines
-        let v6_pattern = format!(
-            r#"(?s)\(symbol\s+"{}"\s+.*?\n  \)\n"#,
-            regex::escape(component_name)
-        );
-        if let Ok(re) = Regex::new(&v6_pattern) {
-            if re.is_match(&content) {
-                let new_content = re.replace(&content, new_data);
-                fs::write(lib_path, new_content.as_ref())
-                    .map_err(KicadError::Io)?;
-                return Ok(());
-            }
+        if !library.remove_symbol(component_name) {
+            return Ok(false);
         }
 
-        // For KiCad v5 format: use (?s) flag to make . match newlines
-        let v5_pattern = format!(
-            r"(?s)DEF\s+{}\s+.*?ENDDEF\n",
-            regex::escape(component_name)
-        );
-        if let Ok(re) = Regex::new(&v5_pattern) {
-            if re.is_match(&content) {
-                let new_content = re.replace(&content, new_data);
-                fs::write(lib_path, new_content.as_ref())
-                    .map_err(KicadError::Io)?;
-                return Ok(());
-            }
-        }
+        let content = library.to_string_pretty();
+        validate_symbol_library(lib_path, &content)?;
+        write_atomic(lib_path, content.as_bytes())?;
 
-        Err(KicadError::SymbolExport(format!("Component {} not found in library", component_name)).into())
+        Ok(true)
     }
 
-    /// Add a component to the library file
-    pub fn add_component(&self, lib_path: &Path, component_data: &str) -> Result<()> {
-        // Lock to prevent concurrent writes to the same symbol library file
-        let _lock = SYMBOL_WRITE_LOCK.lock().unwrap();
+    /// Whether a footprint of this name exists in the `.pretty` directory
+    pub fn footprint_exists(&self, footprint_name: &str) -> bool {
+        self.pretty_dir()
+            .join(format!("{}.kicad_mod", footprint_name))
+            .exists()
+    }
 
-        let mut content = if lib_path.exists() {
-            // Read existing file and remove the closing parenthesis
-            let existing = fs::read_to_string(lib_path)
-                .map_err(KicadError::Io)?;
-            // Remove trailing ')' and whitespace
-            existing.trim_end().trim_end_matches(')').to_string()
+    /// `<library>:<footprint>` reference used in symbol Footprint fields
+    pub fn footprint_reference(&self, footprint_name: &str) -> String {
+        format!("{}:{}", self.library_name, footprint_name)
+    }
+
+    /// Add this library to the `sym-lib-table` or `fp-lib-table` in `table_dir`
+    ///
+    /// With `project` set, `table_dir` is a KiCad project directory and
+    /// libraries inside it are registered relative to `${KIPRJMOD}`; otherwise
+    /// absolute paths are used (e.g. for the global tables in the KiCad
+    /// config directory). Existing entries are left alone. Returns true if the
+    /// table was changed.
+    pub fn register_library(&self, table_dir: &Path, kind: LibTableKind, project: bool) -> Result<bool> {
+        let _lock = LIB_TABLE_LOCK.lock().unwrap();
+
+        let (lib_path, lib_type) = match kind {
+            LibTableKind::Symbol if self.kicad_version.is_legacy() => (self.get_symbol_lib_path(true), "Legacy"),
+            LibTableKind::Symbol => (self.get_symbol_lib_path(false), "KiCad"),
+            LibTableKind::Footprint => (self.pretty_dir(), "KiCad"),
+        };
+        let uri = table_uri(&lib_path, table_dir, project);
+
+        let table_path = table_dir.join(kind.file_name());
+        let mut table = if table_path.exists() {
+            let content = fs::read_to_string(&table_path).map_err(KicadError::Io)?;
+            LibTable::parse(kind, &content)?
         } else {
-            // Create new library file with header (v6 format with proper formatting)
-            if component_data.contains("(symbol") {
-                // v6 format - match Python's formatting exactly
-                String::from("(kicad_symbol_lib\n  (version 20211014)\n  (generator nlbn)")
-            } else {
-                // v5 format
-                String::from("EESchema-LIBRARY Version 2.4\n#encoding utf-8")
-            }
+            LibTable::new(kind, self.kicad_version)
         };
 
-        // Append component
-        content.push('\n');
-        content.push_str(component_data);
+        if let Some(existing) = table.uri(&self.library_name).filter(|existing| *existing != uri) {
+            log::warn!(
+                "{} already has a library named {} ({}), not registering {}",
+                table_path.display(), self.library_name, existing, uri
+            );
+        }
 
-        // Add closing parenthesis for v6 format
-        if component_data.contains("(symbol") {
-            content.push('\n');
-            content.push(')');
+        if !table.add(&self.library_name, lib_type, &uri, "EasyEDA/LCSC parts converted by nlbn") {
+            return Ok(false);
         }
-        content.push('\n');
 
-        fs::write(lib_path, content)
-            .map_err(KicadError::Io)?;
+        fs::create_dir_all(table_dir).map_err(KicadError::Io)?;
+        write_atomic(&table_path, table.to_string_pretty().as_bytes())?;
 
-        Ok(())
+        log::info!("Registered {} in {}", self.library_name, table_path.display());
+
+        Ok(true)
     }
 
-    /// Update an existing component in the library file
-    pub fn update_component(&self, lib_path: &Path, component_name: &str, new_data: &str) -> Result<()> {
-        // Lock to prevent concurrent writes to the same symbol library file
-        let _lock = SYMBOL_WRITE_LOCK.lock().unwrap();
+    /// Insert a `(model ...)` block into an existing footprint that has none
+    ///
+    /// The rest of the file is kept byte for byte. Returns false if the
+    /// footprint does not exist or already references a 3D model.
+    pub fn add_model_to_footprint(&self, footprint_name: &str, model_block: &str) -> Result<bool> {
+        let footprint_path = self.pretty_dir().join(format!("{}.kicad_mod", footprint_name));
+        if !footprint_path.exists() {
+            return Ok(false);
+        }
 
-        let content = fs::read_to_string(lib_path)
+        let content = fs::read_to_string(&footprint_path)
             .map_err(KicadError::Io)?;
-
-        // Try v6 format first: use (?s) flag to make . match newlines
-        let v6_pattern = format!(
-            r#"(?s)\(symbol\s+"{}"\s+.*?\n  \)\n"#,
-            regex::escape(component_name)
-        );
-        if let Ok(re) = Regex::new(&v6_pattern) {
-            if re.is_match(&content) {
-                let new_content = re.replace(&content, new_data);
-                fs::write(lib_path, new_content.as_ref())
-                    .map_err(KicadError::Io)?;
-                return Ok(());
-            }
+        if SExpr::parse(&content)?.find("model").is_some() {
+            return Ok(false);
         }
 
-        // Try v5 format: use (?s) flag to make . match newlines
-        let v5_pattern = format!(
-            r"(?s)DEF\s+{}\s+.*?ENDDEF\n",
-            regex::escape(component_name)
-        );
-        if let Ok(re) = Regex::new(&v5_pattern) {
-            if re.is_match(&content) {
-                let new_content = re.replace(&content, new_data);
-                fs::write(lib_path, new_content.as_ref())
-                    .map_err(KicadError::Io)?;
-                return Ok(());
-            }
-        }
+        // The file parsed, so it ends with the closing parenthesis of the footprint
+        let end = content.rfind(')').expect("parsed footprint ends with ')'");
+        let head = content[..end].trim_end();
+        let updated = format!("{}\n{}{}", head, model_block, &content[end..]);
 
-        Err(KicadError::SymbolExport(format!("Component {} not found in library", component_name)).into())
+        write_atomic(&footprint_path, updated.as_bytes())?;
+
+        log::info!("Added 3D model to footprint: {}", footprint_path.display());
+
+        Ok(true)
     }
 
     /// Write a footprint file
-    pub fn write_footprint(&self, footprint_name: &str, data: &str) -> Result<PathBuf> {
-        let pretty_dir = self.output_path.join("nlbn.pretty");
+    ///
+    /// Returns `None` if the footprint exists and `overwrite` is not set.
+    pub fn write_footprint(&self, footprint_name: &str, data: &str, overwrite: bool) -> Result<Option<PathBuf>> {
+        let pretty_dir = self.pretty_dir();
         let footprint_path = pretty_dir.join(format!("{}.kicad_mod", footprint_name));
 
-        fs::write(&footprint_path, data)
-            .map_err(KicadError::Io)?;
+        if !self.prepare_overwrite(&footprint_path, overwrite)? {
+            return Ok(None);
+        }
+
+        write_atomic(&footprint_path, data.as_bytes())?;
 
         log::info!("Wrote footprint: {}", footprint_path.display());
 
-        Ok(footprint_path)
+        Ok(Some(footprint_path))
     }
 
     /// Write 3D model files
-    pub fn write_3d_model(&self, model_name: &str, wrl_data: &str, step_data: &[u8]) -> Result<(PathBuf, PathBuf)> {
-        let shapes_dir = self.output_path.join("nlbn.3dshapes");
-
-        // Write VRML file
-        let wrl_path = shapes_dir.join(format!("{}.wrl", model_name));
-        fs::write(&wrl_path, wrl_data)
-            .map_err(KicadError::Io)?;
-
-        log::info!("Wrote VRML model: {}", wrl_path.display());
+    pub fn write_3d_model(
+        &self,
+        model_name: &str,
+        wrl_data: &str,
+        step_data: &[u8],
+        overwrite: bool,
+    ) -> Result<(Option<PathBuf>, Option<PathBuf>)> {
+        let wrl_path = self.write_wrl_model(model_name, wrl_data, overwrite)?;
 
         // Write STEP file only if data is provided
-        let step_path = shapes_dir.join(format!("{}.step", model_name));
-        if !step_data.is_empty() {
-            fs::write(&step_path, step_data)
-                .map_err(KicadError::Io)?;
-            log::info!("Wrote STEP model: {}", step_path.display());
-        }
+        let step_path = if step_data.is_empty() {
+            None
+        } else {
+            self.write_step_model(model_name, step_data, overwrite)?
+        };
 
         Ok((wrl_path, step_path))
     }
 
     /// Write only VRML model (when STEP is not available)
-    pub fn write_wrl_model(&self, model_name: &str, wrl_data: &str) -> Result<PathBuf> {
-        let shapes_dir = self.output_path.join("nlbn.3dshapes");
+    ///
+    /// Returns `None` if the model exists and `overwrite` is not set.
+    pub fn write_wrl_model(&self, model_name: &str, wrl_data: &str, overwrite: bool) -> Result<Option<PathBuf>> {
+        let wrl_path = self.model_path(&format!("{}.wrl", model_name));
+
+        if !self.prepare_overwrite(&wrl_path, overwrite)? {
+            return Ok(None);
+        }
 
         // Write VRML file
-        let wrl_path = shapes_dir.join(format!("{}.wrl", model_name));
-        fs::write(&wrl_path, wrl_data)
-            .map_err(KicadError::Io)?;
+        write_atomic(&wrl_path, wrl_data.as_bytes())?;
 
         log::info!("Wrote VRML model: {}", wrl_path.display());
 
-        Ok(wrl_path)
+        Ok(Some(wrl_path))
     }
 
     /// Write only STEP model
-    pub fn write_step_model(&self, model_name: &str, step_data: &[u8]) -> Result<PathBuf> {
-        let shapes_dir = self.output_path.join("nlbn.3dshapes");
+    ///
+    /// Returns `None` if the model exists and `overwrite` is not set.
+    pub fn write_step_model(&self, model_name: &str, step_data: &[u8], overwrite: bool) -> Result<Option<PathBuf>> {
+        let step_path = self.model_path(&format!("{}.step", model_name));
+
+        if !self.prepare_overwrite(&step_path, overwrite)? {
+            return Ok(None);
+        }
 
         // Write STEP file
-        let step_path = shapes_dir.join(format!("{}.step", model_name));
-        fs::write(&step_path, step_data)
-            .map_err(KicadError::Io)?;
+        write_atomic(&step_path, step_data)?;
 
         log::info!("Wrote STEP model: {}", step_path.display());
 
-        Ok(step_path)
+        Ok(Some(step_path))
+    }
+
+    /// Decide whether `path` may be written: false if it exists and
+    /// `overwrite` is not set. With backups enabled, an existing file is
+    /// copied to `<file>.<timestamp>.bak` first (the original stays in place
+    /// until the new file atomically replaces it).
+    fn prepare_overwrite(&self, path: &Path, overwrite: bool) -> Result<bool> {
+        if !path.exists() {
+            return Ok(true);
+        }
+
+        if !overwrite {
+            log::info!("{} already exists, skipping (overwrite=false)", path.display());
+            return Ok(false);
+        }
+
+        if self.keep_backups {
+            let backup_path = backup_path(path);
+            fs::copy(path, &backup_path)
+                .map_err(KicadError::Io)?;
+            log::info!("Backed up {} to {}", path.display(), backup_path.display());
+        }
+
+        Ok(true)
     }
 
     /// Get the symbol library path
     pub fn get_symbol_lib_path(&self, v5: bool) -> PathBuf {
         if v5 {
-            self.output_path.join("nlbn.lib")
+            self.output_path.join(format!("{}.lib", self.library_name))
+        } else {
+            self.output_path.join(format!("{}.kicad_sym", self.library_name))
+        }
+    }
+}
+
+/// Replace `path` with `data` so that readers and crashes see either the old
+/// or the new file, never a partial one: write a temporary file in the same
+/// directory, fsync it, then rename it over `path`
+fn write_atomic(path: &Path, data: &[u8]) -> Result<()> {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let temp_path = dir.join(format!(
+        ".{}.{}-{}.tmp",
+        file_name,
+        std::process::id(),
+        TEMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    let result = (|| {
+        let mut file = fs::File::create(&temp_path)?;
+        file.write_all(data)?;
+        file.sync_all()?;
+        drop(file);
+        fs::rename(&temp_path, path)
+    })();
+
+    if let Err(e) = result {
+        let _ = fs::remove_file(&temp_path);
+        return Err(KicadError::Io(e).into());
+    }
+
+    // Persist the rename itself; directories cannot be opened on Windows
+    #[cfg(unix)]
+    if let Ok(dir) = fs::File::open(dir) {
+        let _ = dir.sync_all();
+    }
+
+    Ok(())
+}
+
+/// Check that updated library content can be read back before it replaces
+/// the file on disk
+fn validate_symbol_library(lib_path: &Path, content: &str) -> Result<()> {
+    let problem = if is_v5_library(lib_path) {
+        let defs = content.lines().filter(|line| line.starts_with("DEF ")).count();
+        let enddefs = content.lines().filter(|line| line.trim_end() == "ENDDEF").count();
+
+        if !content.starts_with("EESchema-LIBRARY") {
+            Some("missing EESchema-LIBRARY header".to_string())
+        } else if defs != enddefs {
+            Some(format!("{} DEF blocks but {} ENDDEF lines", defs, enddefs))
         } else {
-            self.output_path.join("nlbn.kicad_sym")
+            None
         }
+    } else {
+        SymbolLibrary::parse(content).err().map(|e| e.to_string())
+    };
+
+    match problem {
+        Some(problem) => Err(KicadError::SymbolExport(format!(
+            "updated {} would not be readable ({}), left unchanged",
+            lib_path.display(), problem
+        )).into()),
+        None => Ok(()),
+    }
+}
+
+/// Unused `<file>.<timestamp>[.<n>].bak` path next to `path`
+fn backup_path(path: &Path) -> PathBuf {
+    let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+
+    let mut backup = path.with_file_name(format!("{}.{}.bak", file_name, timestamp));
+    let mut n = 1;
+    while backup.exists() {
+        backup = path.with_file_name(format!("{}.{}.{}.bak", file_name, timestamp, n));
+        n += 1;
+    }
+
+    backup
+}
+
+/// `${KIPRJMOD}`-relative URI for libraries inside a project, absolute otherwise
+fn table_uri(lib_path: &Path, table_dir: &Path, project: bool) -> String {
+    let lib_path = std::path::absolute(lib_path).unwrap_or_else(|_| lib_path.to_path_buf());
+    let table_dir = std::path::absolute(table_dir).unwrap_or_else(|_| table_dir.to_path_buf());
+
+    let uri = match lib_path.strip_prefix(&table_dir) {
+        Ok(relative) if project => Path::new("${KIPRJMOD}").join(relative),
+        _ => lib_path,
+    };
+
+    // KiCad accepts forward slashes on every platform
+    uri.to_string_lossy().replace('\\', "/")
+}
+
+/// KiCad's per-user configuration directory, which holds the global library tables
+pub fn kicad_config_dir(version: KicadVersion) -> Option<PathBuf> {
+    let base = if cfg!(windows) {
+        PathBuf::from(std::env::var_os("APPDATA")?)
+    } else if cfg!(target_os = "macos") {
+        PathBuf::from(std::env::var_os("HOME")?).join("Library").join("Preferences")
+    } else {
+        std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| Some(PathBuf::from(std::env::var_os("HOME")?).join(".config")))?
+    };
+
+    let major = match version {
+        KicadVersion::V5 => return Some(base.join("kicad")),
+        KicadVersion::V6 => "6.0",
+        KicadVersion::V7 => "7.0",
+        KicadVersion::V8 => "8.0",
+        KicadVersion::V9 => "9.0",
+    };
+
+    Some(base.join("kicad").join(major))
+}
+
+/// Nicknames end up in file names and `<library>:<name>` references
+pub fn is_valid_library_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.trim() == name
+        && !name.chars().any(|c| {
+            c.is_control() || matches!(c, ':' | '/' | '\\' | '"' | '*' | '?' | '<' | '>' | '|')
+        })
+}
+
+/// Legacy `.lib` libraries use the line-based EESchema format, not S-expressions
+fn is_v5_library(lib_path: &Path) -> bool {
+    lib_path.extension().is_some_and(|ext| ext == "lib")
+}
+
+/// Insert or replace a `DEF ... ENDDEF` block in a legacy library
+fn upsert_v5_component(existing: Option<&str>, component_name: &str, component_data: &str) -> Result<String> {
+    let mut content = existing
+        .unwrap_or("EESchema-LIBRARY Version 2.4\n#encoding utf-8\n")
+        .to_string();
+
+    let v5_pattern = format!(r"(?ms)^DEF\s+{}\s+.*?^ENDDEF[ \t]*\r?\n?", regex::escape(component_name));
+    let re = Regex::new(&v5_pattern)?;
+
+    let mut block = component_data.trim_end().to_string();
+    block.push('\n');
+
+    if let Some(m) = re.find(&content) {
+        content.replace_range(m.range(), &block);
+    } else {
+        if !content.ends_with('\n') {
+            content.push('\n');
+        }
+        content.push_str(&block);
+    }
+
+    Ok(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_output(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("nlbn_library_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_update_in_resaved_library() {
+        let dir = temp_output("resaved");
+        let manager = LibraryManager::new(&dir);
+        let lib_path = manager.get_symbol_lib_path(false);
+
+        // Tab-indented, as written by KiCad 8 itself
+        fs::write(&lib_path, "(kicad_symbol_lib\n\t(version 20231120)\n\t(generator \"kicad_symbol_editor\")\n\t(symbol \"R\"\n\t\t(in_bom yes)\n\t)\n\t(symbol \"C\"\n\t\t(in_bom yes)\n\t)\n)\n").unwrap();
+
+        assert!(!manager.add_or_update_component(&lib_path, "R", "(symbol \"R\" (in_bom no))", false).unwrap());
+        assert!(manager.add_or_update_component(&lib_path, "R", "  (symbol \"R\"\n    (in_bom no)\n  )", true).unwrap());
+        assert!(manager.add_or_update_component(&lib_path, "D", "(symbol \"D\" (in_bom yes))", false).unwrap());
+
+        let library = SymbolLibrary::parse(&fs::read_to_string(&lib_path).unwrap()).unwrap();
+        assert_eq!(library.symbol_names(), vec!["R", "C", "D"]);
+        assert!(library.to_string_pretty().contains("(generator \"kicad_symbol_editor\")"));
+
+        assert!(manager.remove_component(&lib_path, "C").unwrap());
+        assert!(!manager.component_exists(&lib_path, "C").unwrap());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_library_name() {
+        let dir = temp_output("name");
+        let manager = LibraryManager::new(&dir).with_library_name("power");
+        manager.create_directories().unwrap();
+
+        assert!(dir.join("power.pretty").is_dir());
+        assert!(dir.join("power.3dshapes").is_dir());
+        assert_eq!(manager.get_symbol_lib_path(false), dir.join("power.kicad_sym"));
+        assert_eq!(manager.footprint_reference("LM7805"), "power:LM7805");
+
+        assert!(LibraryManager::new(&dir).with_library_name("a:b").create_directories().is_err());
+        assert!(!is_valid_library_name(""));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_add_model_to_footprint() {
+        let dir = temp_output("model");
+        let manager = LibraryManager::new(&dir);
+        manager.create_directories().unwrap();
+
+        let model = "  (model \"${KIPRJMOD}/nlbn.3dshapes/R.step\")\n";
+        assert!(!manager.add_model_to_footprint("R", model).unwrap());
+
+        manager.write_footprint("R", "(footprint \"R\"\n  (layer \"F.Cu\")\n)\n", false).unwrap();
+        assert!(manager.add_model_to_footprint("R", model).unwrap());
+        assert!(!manager.add_model_to_footprint("R", model).unwrap());
+
+        let content = fs::read_to_string(dir.join("nlbn.pretty").join("R.kicad_mod")).unwrap();
+        assert_eq!(content, "(footprint \"R\"\n  (layer \"F.Cu\")\n  (model \"${KIPRJMOD}/nlbn.3dshapes/R.step\")\n)\n");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_footprint_overwrite_and_backup() {
+        let dir = temp_output("overwrite");
+        let manager = LibraryManager::new(&dir);
+        manager.create_directories().unwrap();
+        let path = dir.join("nlbn.pretty").join("R.kicad_mod");
+
+        assert_eq!(manager.write_footprint("R", "(footprint \"R\")\n", false).unwrap(), Some(path.clone()));
+        assert_eq!(manager.write_footprint("R", "(footprint \"new\")\n", false).unwrap(), None);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "(footprint \"R\")\n");
+        assert_eq!(manager.write_step_model("R", b"ISO", false).unwrap(), Some(dir.join("nlbn.3dshapes").join("R.step")));
+        assert_eq!(manager.write_step_model("R", b"ISO-2", false).unwrap(), None);
+
+        // Overwriting with backups keeps the previous file next to the new one
+        let manager = manager.with_backups(true);
+        manager.write_footprint("R", "(footprint \"new\")\n", true).unwrap();
+        manager.write_footprint("R", "(footprint \"newer\")\n", true).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "(footprint \"newer\")\n");
+
+        let mut backups: Vec<String> = fs::read_dir(dir.join("nlbn.pretty")).unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .filter(|name| name.ends_with(".bak"))
+            .collect();
+        backups.sort();
+        assert_eq!(backups.len(), 2);
+        assert!(backups[0].starts_with("R.kicad_mod."));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_invalid_update_keeps_library() {
+        let dir = temp_output("invalid");
+        let manager = LibraryManager::new(&dir);
+        let lib_path = manager.get_symbol_lib_path(true);
+
+        manager.add_component(&lib_path, "DEF R R 0 0 N Y 1 F N\nDRAW\nENDDRAW\nENDDEF\n").unwrap();
+        let before = fs::read_to_string(&lib_path).unwrap();
+
+        // A truncated block would leave the library unreadable
+        let result = manager.add_component(&lib_path, "DEF C C 0 0 N Y 1 F N\nDRAW\n");
+        assert!(result.is_err());
+        assert_eq!(fs::read_to_string(&lib_path).unwrap(), before);
+
+        // No temporary files are left behind
+        let leftovers: Vec<_> = fs::read_dir(&dir).unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .filter(|name| name.to_string_lossy().ends_with(".tmp"))
+            .collect();
+        assert!(leftovers.is_empty());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_locked_library_times_out() {
+        let dir = temp_output("lock");
+        let manager = LibraryManager::new(&dir).with_lock_timeout(Duration::from_millis(100));
+        let lib_path = manager.get_symbol_lib_path(true);
+
+        // Another process holding the lock (a separate open file behaves the same)
+        let holder = fs::File::create(dir.join("nlbn.lib.lock")).unwrap();
+        holder.lock().unwrap();
+
+        let err = manager.add_component(&lib_path, "DEF R R 0 0 N Y 1 F N\nENDDEF\n").unwrap_err();
+        assert!(err.to_string().contains("locked by another process"), "{}", err);
+        assert!(!lib_path.exists());
+
+        drop(holder);
+        manager.add_component(&lib_path, "DEF R R 0 0 N Y 1 F N\nENDDEF\n").unwrap();
+        assert!(lib_path.exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_register_library() {
+        let dir = temp_output("tables");
+        let project = dir.join("board");
+        let manager = LibraryManager::new(&project.join("libs")).with_library_name("mcu");
+
+        assert!(manager.register_library(&project, LibTableKind::Symbol, true).unwrap());
+        assert!(manager.register_library(&project, LibTableKind::Footprint, true).unwrap());
+        assert!(!manager.register_library(&project, LibTableKind::Footprint, true).unwrap());
+
+        let fp_table = fs::read_to_string(project.join("fp-lib-table")).unwrap();
+        assert_eq!(fp_table.matches("(name \"mcu\")").count(), 1);
+        assert!(fp_table.contains("(uri \"${KIPRJMOD}/libs/mcu.pretty\")"));
+
+        // A global table gets absolute paths
+        let config = dir.join("config");
+        manager.register_library(&config, LibTableKind::Symbol, false).unwrap();
+        let sym_table = fs::read_to_string(config.join("sym-lib-table")).unwrap();
+        assert!(!sym_table.contains("KIPRJMOD"));
+        assert!(sym_table.contains("mcu.kicad_sym"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_update_v5_library() {
+        let dir = temp_output("v5");
+        let manager = LibraryManager::new(&dir);
+        let lib_path = manager.get_symbol_lib_path(true);
+
+        manager.add_component(&lib_path, "DEF R R 0 40 Y Y 1 F N\nENDDEF\n").unwrap();
+        manager.add_component(&lib_path, "DEF RN R 0 40 Y Y 1 F N\nENDDEF\n").unwrap();
+        manager.update_component(&lib_path, "R", "DEF R R 0 0 Y Y 1 F N\nENDDEF\n").unwrap();
+
+        let content = fs::read_to_string(&lib_path).unwrap();
+        assert_eq!(content.matches("DEF R R").count(), 1);
+        assert!(content.contains("DEF R R 0 0"));
+        assert!(content.contains("DEF RN R"));
+
+        let _ = fs::remove_dir_all(&dir);
     }
 }
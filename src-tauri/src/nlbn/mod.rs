@@ -7,14 +7,59 @@ pub mod error;
 
 // Re-export commonly used types
 pub use error::{AppError, Result};
-pub use easyeda::{EasyedaApi, SymbolImporter, FootprintImporter};
+pub use easyeda::{
+    EasyedaApi, ComponentSource, FixtureSource, CachedSource, ResponseCache,
+    SymbolImporter, FootprintImporter,
+};
 pub use kicad::{SymbolExporter, FootprintExporter, ModelExporter};
 pub use converter::Converter;
 pub use library::LibraryManager;
 
 // Enum types (from cli.rs)
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum KicadVersion {
     V5,
     V6,
+    V7,
+    V8,
+    V9,
+}
+
+impl KicadVersion {
+    /// Version for a KiCad major release number (5-9)
+    pub fn from_major(major: u32) -> Option<Self> {
+        match major {
+            5 => Some(KicadVersion::V5),
+            6 => Some(KicadVersion::V6),
+            7 => Some(KicadVersion::V7),
+            8 => Some(KicadVersion::V8),
+            9 => Some(KicadVersion::V9),
+            _ => None,
+        }
+    }
+
+    /// KiCad 5 writes the legacy line-based formats
+    pub fn is_legacy(&self) -> bool {
+        *self == KicadVersion::V5
+    }
+
+    /// `(version ...)` written in `.kicad_sym` libraries
+    pub fn symbol_lib_version(&self) -> &'static str {
+        match self {
+            KicadVersion::V5 | KicadVersion::V6 => "20211014",
+            KicadVersion::V7 => "20220914",
+            KicadVersion::V8 => "20231120",
+            KicadVersion::V9 => "20241209",
+        }
+    }
+
+    /// `(version ...)` written in `.kicad_mod` footprints
+    pub fn footprint_version(&self) -> &'static str {
+        match self {
+            KicadVersion::V5 | KicadVersion::V6 => "20211014",
+            KicadVersion::V7 => "20221018",
+            KicadVersion::V8 => "20240108",
+            KicadVersion::V9 => "20241229",
+        }
+    }
 }
@@ -1,4 +1,53 @@
+use crate::nlbn::library::{kicad_config_dir, DEFAULT_LIBRARY_NAME, DEFAULT_LOCK_TIMEOUT};
+use crate::nlbn::KicadVersion;
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Where the footprint origin is placed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum FootprintOrigin {
+    /// The package origin set in EasyEDA
+    #[default]
+    EasyedaOrigin,
+    /// The center of the bounding box of all pads and graphics
+    BboxCenter,
+    /// The center of the pads
+    PadCenter,
+}
+
+/// 3D model path mode chosen in the options
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelPathMode {
+    /// `${KIPRJMOD}` paths, for libraries inside the project
+    #[default]
+    ProjectRelative,
+    /// Paths through the `model_path_var` path variable
+    Variable,
+    /// Absolute paths on this machine
+    Absolute,
+}
+
+/// How footprints reference their 3D models
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum ModelPath {
+    /// `${KIPRJMOD}/<library>.3dshapes/...`, for libraries inside the project
+    #[default]
+    ProjectRelative,
+    /// `${VAR}/<library>.3dshapes/...`, where `VAR` points at the output directory
+    Variable(String),
+    /// The absolute path of the model on this machine
+    Absolute,
+}
+
+/// Directory whose `sym-lib-table` and `fp-lib-table` the libraries are added to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LibTableTarget {
+    pub dir: PathBuf,
+    /// A KiCad project directory (`${KIPRJMOD}` URIs) rather than the global config
+    pub project: bool,
+}
 
 /// Conversion options
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -8,8 +57,118 @@ pub struct ConversionOptions {
     pub convert_footprint: bool,
     pub convert_3d: bool,
     pub kicad_v5: bool,
-    pub project_relative: bool,
+    /// KiCad major version (6-9) to write when `kicad_v5` is not set
+    #[serde(default = "default_kicad_version")]
+    pub kicad_version: u32,
+    /// How footprints reference their 3D models; when absent, derived from
+    /// `model_path_var` and the deprecated `project_relative`
+    #[serde(default)]
+    pub model_path_mode: Option<ModelPathMode>,
+    /// Path variable for the `variable` mode, e.g. `NLBN_3DMODELS`
+    #[serde(default)]
+    pub model_path_var: String,
+    /// Deprecated, use `model_path_mode`: `false` selects absolute paths
+    #[serde(default)]
+    pub project_relative: Option<bool>,
     pub overwrite: bool,
+    /// Rename overwritten footprints and 3D models to `<file>.<timestamp>.bak`
+    #[serde(default)]
+    pub keep_backups: bool,
+    /// Seconds to wait for another process updating the symbol library
+    #[serde(default = "default_lock_timeout_secs")]
+    pub lock_timeout_secs: u64,
+    /// Bypass the response cache and re-download component data and models
+    #[serde(default)]
+    pub force_refresh: bool,
+    #[serde(default)]
+    pub footprint_origin: FootprintOrigin,
+    /// Grid symbol pins are snapped to, in mils (0 disables snapping)
+    #[serde(default = "default_pin_grid_mil")]
+    pub pin_grid_mil: u32,
+    /// Library nickname: `<name>.kicad_sym`, `<name>.pretty`, `<name>.3dshapes`
+    #[serde(default = "default_library_name")]
+    pub library_name: String,
+    /// KiCad project directory to register the libraries in (empty: don't register),
+    /// or the config directory override when `lib_table_global` is set
+    #[serde(default)]
+    pub lib_table_dir: String,
+    /// Register the libraries in KiCad's global library tables
+    #[serde(default)]
+    pub lib_table_global: bool,
+}
+
+fn default_kicad_version() -> u32 {
+    6
+}
+
+fn default_lock_timeout_secs() -> u64 {
+    DEFAULT_LOCK_TIMEOUT.as_secs()
+}
+
+fn default_library_name() -> String {
+    DEFAULT_LIBRARY_NAME.to_string()
+}
+
+fn default_pin_grid_mil() -> u32 {
+    50
+}
+
+impl ConversionOptions {
+    /// Output format selected by `kicad_v5` and `kicad_version`
+    pub fn kicad_format(&self) -> KicadVersion {
+        if self.kicad_v5 {
+            return KicadVersion::V5;
+        }
+
+        KicadVersion::from_major(self.kicad_version).unwrap_or_else(|| {
+            log::warn!("Unsupported KiCad version {}, writing KiCad 6 files", self.kicad_version);
+            KicadVersion::V6
+        })
+    }
+
+    /// Library tables selected by `lib_table_dir` and `lib_table_global`
+    pub fn lib_table_target(&self) -> Option<LibTableTarget> {
+        let dir = self.lib_table_dir.trim();
+
+        if self.lib_table_global {
+            let dir = if dir.is_empty() {
+                let config_dir = kicad_config_dir(self.kicad_format());
+                if config_dir.is_none() {
+                    log::warn!("KiCad config directory not found, not registering libraries");
+                }
+                config_dir?
+            } else {
+                PathBuf::from(dir)
+            };
+            Some(LibTableTarget { dir, project: false })
+        } else if dir.is_empty() {
+            None
+        } else {
+            Some(LibTableTarget { dir: PathBuf::from(dir), project: true })
+        }
+    }
+
+    /// 3D model path selected by `model_path_mode` and `model_path_var`
+    pub fn model_path(&self) -> ModelPath {
+        let var = self.model_path_var.trim().trim_start_matches("${").trim_end_matches('}');
+
+        let mode = self.model_path_mode.unwrap_or(match (self.project_relative, var.is_empty()) {
+            (Some(true), _) => ModelPathMode::ProjectRelative,
+            (_, false) => ModelPathMode::Variable,
+            (Some(false), true) => ModelPathMode::Absolute,
+            (None, true) => ModelPathMode::ProjectRelative,
+        });
+
+        match mode {
+            ModelPathMode::ProjectRelative => ModelPath::ProjectRelative,
+            ModelPathMode::Absolute => ModelPath::Absolute,
+            ModelPathMode::Variable if var.is_empty() => {
+                log::warn!("No 3D model path variable set, using project-relative paths");
+                ModelPath::ProjectRelative
+            }
+            ModelPathMode::Variable => ModelPath::Variable(var.to_string()),
+        }
+    }
 }
 
 impl Default for ConversionOptions {
@@ -20,8 +179,19 @@ impl Default for ConversionOptions {
             convert_footprint: true,
             convert_3d: true,
             kicad_v5: false,
-            project_relative: false,
+            kicad_version: default_kicad_version(),
+            model_path_mode: None,
+            model_path_var: String::new(),
+            project_relative: None,
             overwrite: false,
+            keep_backups: false,
+            lock_timeout_secs: default_lock_timeout_secs(),
+            force_refresh: false,
+            footprint_origin: FootprintOrigin::default(),
+            pin_grid_mil: default_pin_grid_mil(),
+            library_name: default_library_name(),
+            lib_table_dir: String::new(),
+            lib_table_global: false,
         }
     }
 }
@@ -54,6 +224,15 @@ pub struct ProgressUpdate {
     pub status: String,
 }
 
+/// Response cache summary
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheInfo {
+    pub path: String,
+    pub components: usize,
+    pub models: usize,
+    pub total_bytes: u64,
+}
+
 /// History entry
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HistoryEntry {
@@ -64,3 +243,30 @@ pub struct HistoryEntry {
     pub timestamp: String,
     pub output_dir: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Options as sent by callers that predate `model_path_mode`
+    fn options(extra: &str) -> ConversionOptions {
+        serde_json::from_str(&format!(
+            r#"{{"output_dir": "out", "convert_symbol": true, "convert_footprint": true,
+                "convert_3d": true, "kicad_v5": false, "overwrite": false{extra}}}"#
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_legacy_project_relative() {
+        assert_eq!(options(r#", "project_relative": false"#).model_path(), ModelPath::Absolute);
+        assert_eq!(options(r#", "project_relative": true"#).model_path(), ModelPath::ProjectRelative);
+        assert_eq!(options("").model_path(), ModelPath::ProjectRelative);
+    }
+
+    #[test]
+    fn test_model_path_mode_wins_over_project_relative() {
+        let opts = options(r#", "project_relative": false, "model_path_mode": "project_relative""#);
+        assert_eq!(opts.model_path(), ModelPath::ProjectRelative);
+    }
+}
@@ -0,0 +1,149 @@
+//! Golden-file regression tests for symbol and footprint export.
+//!
+//! Each `tests/golden/<case>.json` holds a `/api/products/{id}/components`
+//! response body in the shape the EasyEDA API returns (record a new case with
+//! `curl https://easyeda.com/api/products/<LCSC ID>/components`). The case is
+//! parsed like a live response, run through import, conversion and export and
+//! compared against the checked-in snapshots next to it:
+//!
+//! - `<case>.kicad_sym` - KiCad 6 symbol
+//! - `<case>.lib` - KiCad 5 symbol
+//! - `<case>.kicad_mod` - footprint
+//! - `<case>.v5.kicad_mod` - KiCad 5 footprint
+//! - `<case>.v9.kicad_sym` / `<case>.v9.kicad_mod` - KiCad 9 symbol library and footprint
+//!
+//! The KiCad 9 files must also survive a parse and re-layout unchanged, as
+//! they do when KiCad loads and saves them.
+//!
+//! After an intentional output change, re-generate the snapshots with
+//! `NLBN_BLESS=1 cargo test --test golden` and review the diff.
+
+use nlbn_new_lib::converter_impl::{sanitize_component_name, ComponentConverter};
+use nlbn_new_lib::nlbn::easyeda::ComponentData;
+use nlbn_new_lib::nlbn::EasyedaApi;
+use nlbn_new_lib::nlbn::kicad::{SExpr, SymbolLibrary};
+use nlbn_new_lib::nlbn::KicadVersion;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn golden_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("golden")
+}
+
+fn bless_mode() -> bool {
+    std::env::var("NLBN_BLESS").is_ok_and(|v| !v.is_empty() && v != "0")
+}
+
+fn load_cases() -> Vec<(String, ComponentData)> {
+    let mut cases = Vec::new();
+
+    for entry in fs::read_dir(golden_dir()).expect("missing tests/golden directory") {
+        let path = entry.unwrap().path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            let name = path.file_stem().unwrap().to_string_lossy().to_string();
+            let body = fs::read(&path).unwrap();
+            let data = parse_case(&body)
+                .unwrap_or_else(|e| panic!("Invalid golden case {}: {}", path.display(), e));
+            cases.push((name, data));
+        }
+    }
+
+    cases.sort_by(|a, b| a.0.cmp(&b.0));
+    assert!(!cases.is_empty(), "no golden cases found");
+    cases
+}
+
+/// Parse a recorded response; the LCSC ID comes from the response itself
+fn parse_case(body: &[u8]) -> Result<ComponentData, String> {
+    let response: serde_json::Value = serde_json::from_slice(body).map_err(|e| e.to_string())?;
+    let lcsc_id = response.pointer("/result/lcsc/number")
+        .and_then(|v| v.as_str())
+        .ok_or("missing result.lcsc.number")?;
+
+    EasyedaApi::parse_component_response(lcsc_id, body).map_err(|e| e.to_string())
+}
+
+/// Check that a file is already in KiCad's canonical layout
+fn check_round_trip(case: &str, kind: &str, content: &str) -> Option<String> {
+    let relaid = SExpr::parse(content).unwrap().to_pretty_string();
+    (relaid != content).then(|| format!("{}: KiCad 9 {} changes when parsed and written again", case, kind))
+}
+
+/// Compare output with a snapshot (or write it in bless mode); returns a failure description
+fn check_snapshot(path: &Path, actual: &str) -> Option<String> {
+    if bless_mode() {
+        fs::write(path, actual).unwrap();
+        return None;
+    }
+
+    let expected = match fs::read_to_string(path) {
+        Ok(expected) => expected.replace("\r\n", "\n"),
+        Err(_) => return Some(format!("{}: snapshot missing (run with NLBN_BLESS=1)", path.display())),
+    };
+
+    if expected == actual {
+        return None;
+    }
+
+    let line = expected.lines()
+        .zip(actual.lines())
+        .position(|(e, a)| e != a)
+        .unwrap_or_else(|| expected.lines().count().min(actual.lines().count()));
+
+    Some(format!(
+        "{}: output differs at line {}\n  expected: {}\n  actual:   {}",
+        path.display(),
+        line + 1,
+        expected.lines().nth(line).unwrap_or("<end of file>"),
+        actual.lines().nth(line).unwrap_or("<end of file>"),
+    ))
+}
+
+#[test]
+fn golden_symbols_and_footprints() {
+    let dir = golden_dir();
+    let output_path = std::env::temp_dir().join("nlbn_golden_unused");
+    let v6 = ComponentConverter::new(&output_path, KicadVersion::V6);
+    let v5 = ComponentConverter::new(&output_path, KicadVersion::V5);
+    let v9 = ComponentConverter::new(&output_path, KicadVersion::V9);
+
+    let mut failures = Vec::new();
+
+    for (case, data) in load_cases() {
+        let name = sanitize_component_name(&data.title);
+        let footprint_ref = format!("nlbn:{}", name);
+        let footprint_ref = Some(footprint_ref.as_str());
+
+        let symbol = v6.export_symbol(&data, &name, footprint_ref).unwrap();
+        failures.extend(check_snapshot(&dir.join(format!("{}.kicad_sym", case)), &symbol));
+
+        let symbol_v5 = v5.export_symbol(&data, &name, footprint_ref).unwrap();
+        failures.extend(check_snapshot(&dir.join(format!("{}.lib", case)), &symbol_v5));
+
+        let model_file = data.model_3d.as_ref().map(|_| format!("{}.wrl", name));
+        let model_file = model_file.as_deref();
+        let footprint = v6.export_footprint(&data, &name, model_file).unwrap();
+        assert!(
+            footprint.contains("(fp_text user \"REF**\" (at 0 -3)")
+                && footprint.contains("(effects (font (size 1 1) (thickness 0.15)))"),
+            "{}: reference text must be 1 mm high, 3 mm above the origin",
+            case
+        );
+        failures.extend(check_snapshot(&dir.join(format!("{}.kicad_mod", case)), &footprint));
+
+        let footprint_v5 = v5.export_footprint(&data, &name, model_file).unwrap();
+        failures.extend(check_snapshot(&dir.join(format!("{}.v5.kicad_mod", case)), &footprint_v5));
+
+        let mut library_v9 = SymbolLibrary::new(KicadVersion::V9);
+        library_v9.upsert_symbol(&v9.export_symbol(&data, &name, footprint_ref).unwrap()).unwrap();
+        let library_v9 = library_v9.to_string_pretty();
+        failures.extend(check_round_trip(&case, "symbol library", &library_v9));
+        failures.extend(check_snapshot(&dir.join(format!("{}.v9.kicad_sym", case)), &library_v9));
+
+        let footprint_v9 = v9.export_footprint(&data, &name, model_file).unwrap();
+        failures.extend(check_round_trip(&case, "footprint", &footprint_v9));
+        failures.extend(check_snapshot(&dir.join(format!("{}.v9.kicad_mod", case)), &footprint_v9));
+    }
+
+    assert!(failures.is_empty(), "golden output mismatch:\n{}", failures.join("\n"));
+}
@@ -8,8 +8,18 @@ interface ConversionOptions {
   convert_footprint: boolean;
   convert_3d: boolean;
   kicad_v5: boolean;
-  project_relative: boolean;
+  kicad_version: number;
+  model_path_mode: "project_relative" | "variable" | "absolute";
+  model_path_var: string;
   overwrite: boolean;
+  keep_backups: boolean;
+  lock_timeout_secs: number;
+  force_refresh: boolean;
+  footprint_origin: "easyeda_origin" | "bbox_center" | "pad_center";
+  pin_grid_mil: number;
+  library_name: string;
+  lib_table_dir: string;
+  lib_table_global: boolean;
 }
 
 interface ConversionResult {
@@ -36,6 +46,13 @@ interface HistoryEntry {
   output_dir: string;
 }
 
+interface CacheInfo {
+  path: string;
+  components: number;
+  models: number;
+  total_bytes: number;
+}
+
 interface ProgressUpdate {
   current: number;
   total: number;
@@ -48,8 +65,13 @@ let lcscIdInput: HTMLInputElement;
 let outputDirInput: HTMLInputElement;
 let convertBtn: HTMLButtonElement;
 let selectDirBtn: HTMLButtonElement;
+let libTableDirInput: HTMLInputElement;
+let selectProjectBtn: HTMLButtonElement;
+let clearProjectBtn: HTMLButtonElement;
 let importFileBtn: HTMLButtonElement;
 let clearHistoryBtn: HTMLButtonElement;
+let clearCacheBtn: HTMLButtonElement;
+let cacheInfoDiv: HTMLElement;
 let resultMessageDiv: HTMLElement;
 let historyContainer: HTMLElement;
 
@@ -58,6 +80,14 @@ let optSymbol: HTMLInputElement;
 let optFootprint: HTMLInputElement;
 let opt3D: HTMLInputElement;
 let optOverwrite: HTMLInputElement;
+let optBackup: HTMLInputElement;
+let optForceRefresh: HTMLInputElement;
+let optModelPathMode: HTMLSelectElement;
+let optModelPathVar: HTMLInputElement;
+let optLibTableGlobal: HTMLInputElement;
+let optKicadVersion: HTMLSelectElement;
+let optFootprintOrigin: HTMLSelectElement;
+let optLibraryName: HTMLInputElement;
 
 // Progress listener cleanup
 let progressUnlisten: UnlistenFn | null = null;
@@ -70,9 +100,19 @@ function getConversionOptions(): ConversionOptions {
     convert_symbol: optSymbol.checked,
     convert_footprint: optFootprint.checked,
     convert_3d: opt3D.checked,
-    kicad_v5: false,
-    project_relative: false,
+    kicad_v5: optKicadVersion.value === "5",
+    kicad_version: Number(optKicadVersion.value),
+    model_path_mode: optModelPathMode.value as ConversionOptions["model_path_mode"],
+    model_path_var: optModelPathVar.value.trim(),
     overwrite: optOverwrite.checked,
+    keep_backups: optBackup.checked,
+    lock_timeout_secs: 30,
+    force_refresh: optForceRefresh.checked,
+    footprint_origin: optFootprintOrigin.value as ConversionOptions["footprint_origin"],
+    pin_grid_mil: 50,
+    library_name: optLibraryName.value.trim() || "nlbn",
+    lib_table_dir: optLibTableGlobal.checked ? "" : libTableDirInput.value,
+    lib_table_global: optLibTableGlobal.checked,
   };
 }
 
@@ -179,6 +219,7 @@ async function convertComponent() {
       if (result.success) {
         await loadHistory();
       }
+      await loadCacheInfo();
     } else {
       // Batch conversion
       try {
@@ -198,8 +239,9 @@ async function convertComponent() {
 
         showBatchResult(batchResult);
 
-        // Refresh history after batch conversion
+        // Refresh history and cache size after batch conversion
         await loadHistory();
+        await loadCacheInfo();
       } finally {
         // Clean up listener
         if (progressUnlisten) {
@@ -232,6 +274,16 @@ async function selectOutputDirectory() {
   }
 }
 
+// Select KiCad project directory to register the libraries in
+async function selectProjectDirectory() {
+  try {
+    const path: string = await invoke("select_project_directory");
+    libTableDirInput.value = path;
+  } catch (error) {
+    console.log("Directory selection cancelled or failed:", error);
+  }
+}
+
 // Import LCSC IDs from text file
 async function importIdsFromFile() {
   try {
@@ -325,6 +377,36 @@ async function clearHistory() {
   }
 }
 
+// Load and display response cache size
+async function loadCacheInfo() {
+  try {
+    const info: CacheInfo = await invoke("get_cache_info");
+    const size = info.total_bytes < 1024 * 1024
+      ? `${(info.total_bytes / 1024).toFixed(1)} KB`
+      : `${(info.total_bytes / (1024 * 1024)).toFixed(1)} MB`;
+
+    cacheInfoDiv.textContent = `${info.components} component(s), ${info.models} model(s), ${size}`;
+    cacheInfoDiv.title = info.path;
+  } catch (error) {
+    console.error("Failed to load cache info:", error);
+    cacheInfoDiv.textContent = "Unavailable";
+  }
+}
+
+// Clear response cache
+async function clearCache() {
+  if (!confirm("Are you sure you want to clear the response cache?")) {
+    return;
+  }
+
+  try {
+    await invoke("clear_cache");
+    await loadCacheInfo();
+  } catch (error) {
+    alert(`Failed to clear cache: ${error}`);
+  }
+}
+
 // Show batch result - only display failed components
 function showBatchResult(batchResult: BatchResult) {
   resultMessageDiv.style.display = "block";
@@ -472,8 +554,13 @@ window.addEventListener("DOMContentLoaded", () => {
   outputDirInput = document.querySelector("#output-dir")!;
   convertBtn = document.querySelector("#convert-btn")!;
   selectDirBtn = document.querySelector("#select-dir-btn")!;
+  libTableDirInput = document.querySelector("#lib-table-dir")!;
+  selectProjectBtn = document.querySelector("#select-project-btn")!;
+  clearProjectBtn = document.querySelector("#clear-project-btn")!;
   importFileBtn = document.querySelector("#import-file-btn")!;
   clearHistoryBtn = document.querySelector("#clear-history-btn")!;
+  clearCacheBtn = document.querySelector("#clear-cache-btn")!;
+  cacheInfoDiv = document.querySelector("#cache-info")!;
   resultMessageDiv = document.querySelector("#result-message")!;
   historyContainer = document.querySelector("#history-container")!;;
 
@@ -482,12 +569,41 @@ window.addEventListener("DOMContentLoaded", () => {
   optFootprint = document.querySelector("#opt-footprint")!;
   opt3D = document.querySelector("#opt-3d")!;
   optOverwrite = document.querySelector("#opt-overwrite")!;
+  optBackup = document.querySelector("#opt-backup")!;
+  optForceRefresh = document.querySelector("#opt-force-refresh")!;
+  optModelPathMode = document.querySelector("#opt-model-path-mode")!;
+  optModelPathVar = document.querySelector("#opt-model-path-var")!;
+  optLibTableGlobal = document.querySelector("#opt-lib-table-global")!;
+  optKicadVersion = document.querySelector("#opt-kicad-version")!;
+  optFootprintOrigin = document.querySelector("#opt-footprint-origin")!;
+  optLibraryName = document.querySelector("#opt-library-name")!;
 
   // Event listeners
   convertBtn.addEventListener("click", convertComponent);
   selectDirBtn.addEventListener("click", selectOutputDirectory);
+  selectProjectBtn.addEventListener("click", selectProjectDirectory);
+  clearProjectBtn.addEventListener("click", () => {
+    libTableDirInput.value = "";
+  });
   importFileBtn.addEventListener("click", importIdsFromFile);
   clearHistoryBtn.addEventListener("click", clearHistory);
+  clearCacheBtn.addEventListener("click", clearCache);
+
+  // The path variable only applies to the "variable" mode
+  const updateModelPathVar = () => {
+    optModelPathVar.disabled = optModelPathMode.value !== "variable";
+  };
+  optModelPathMode.addEventListener("change", updateModelPathVar);
+  updateModelPathVar();
+
+  // The global tables replace the project ones
+  const updateLibTableDir = () => {
+    selectProjectBtn.disabled = optLibTableGlobal.checked;
+    clearProjectBtn.disabled = optLibTableGlobal.checked;
+    libTableDirInput.disabled = optLibTableGlobal.checked;
+  };
+  optLibTableGlobal.addEventListener("change", updateLibTableDir);
+  updateLibTableDir();
 
   // Allow Enter key to trigger conversion
   lcscIdInput.addEventListener("keypress", (e) => {
@@ -513,6 +629,7 @@ window.addEventListener("DOMContentLoaded", () => {
     themeToggleBtn.textContent = next === "dark" ? "☀️" : "🌙";
   });
 
-  // Load history on startup
+  // Load history and cache size on startup
   loadHistory();
+  loadCacheInfo();
 });